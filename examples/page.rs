@@ -0,0 +1,42 @@
+use sap_scripting::*;
+
+/// The first screen of FPE1, bound in one go.
+#[derive(SapScreen)]
+struct PostDocument {
+    #[sap(id = "wnd[0]/usr/ctxtFKKKO-BLART")]
+    doc_type: GuiCTextField,
+    #[sap(id = "wnd[0]/usr/ctxtFKKKO-WAERS")]
+    currency: GuiCTextField,
+    #[sap(id = "wnd[0]/usr/txtFKKKO-XBLNR")]
+    reference: GuiTextField,
+}
+
+/// Demonstrate the charge example using a page object
+fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let com_instance = SAPComInstance::new().expect("Couldn't get COM instance");
+    let wrapper = com_instance
+        .sap_wrapper()
+        .expect("Couldn't get SAP wrapper");
+    let engine = wrapper
+        .scripting_engine()
+        .expect("Couldn't get GuiApplication instance");
+
+    let connection: GuiConnection = sap_scripting::GuiApplicationExt::children(&engine)?
+        .element_at(0)?
+        .downcast()
+        .expect("expected connection, but got something else!");
+    let session: GuiSession = sap_scripting::GuiConnectionExt::children(&connection)?
+        .element_at(0)?
+        .downcast()
+        .expect("expected session, but got something else!");
+
+    session.start_transaction("fpe1".to_string())?;
+
+    // Reports every missing or mistyped field at once.
+    let screen = PostDocument::bind(&session)?;
+    screen.doc_type.set_text("P1".to_string())?;
+    screen.currency.set_text("GBP".to_string())?;
+    screen.reference.set_text("XA12345678".to_string())?;
+
+    Ok(())
+}
@@ -0,0 +1,107 @@
+//! Derive macros for SAP Scripting for Rust.
+//!
+//! These are re-exported by the `sap-scripting` crate when its `derive` feature is enabled.
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{Data, DeriveInput, Fields, LitStr, parse_macro_input, spanned::Spanned};
+
+/// Derive `SapScreen` for a struct of SAP GUI components.
+///
+/// Every field must be annotated with the ID of the component it binds to, for example
+/// `#[sap(id = "wnd[0]/usr/ctxtFKKKO-BLART")]`.
+#[proc_macro_derive(SapScreen, attributes(sap))]
+pub fn derive_sap_screen(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match expand(input) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+fn expand(input: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(syn::Error::new(
+                    input.span(),
+                    "SapScreen can only be derived for structs with named fields",
+                ));
+            }
+        },
+        _ => {
+            return Err(syn::Error::new(
+                input.span(),
+                "SapScreen can only be derived for structs",
+            ));
+        }
+    };
+
+    let mut names = vec![];
+    let mut binds = vec![];
+    for field in fields {
+        let name = field.ident.as_ref().expect("named fields have identifiers");
+        let ty = &field.ty;
+        let id = component_id(field)?;
+        let name_str = name.to_string();
+        binds.push(quote! {
+            let #name = ::sap_scripting::page::bind_field::<#ty>(
+                __sap_session,
+                #name_str,
+                #id,
+                &mut __sap_failures,
+            );
+        });
+        names.push(name);
+    }
+
+    let construct = if names.is_empty() {
+        quote!(::std::result::Result::Ok(Self {}))
+    } else {
+        quote! {
+            match (#(#names,)*) {
+                (#(::std::option::Option::Some(#names),)*) => {
+                    ::std::result::Result::Ok(Self { #(#names),* })
+                }
+                _ => ::std::result::Result::Err(::sap_scripting::Error::Bind(__sap_failures)),
+            }
+        }
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::sap_scripting::SapScreen for #ident #ty_generics #where_clause {
+            fn bind(
+                __sap_session: &::sap_scripting::GuiSession,
+            ) -> ::sap_scripting::Result<Self, ::sap_scripting::Error> {
+                #[allow(unused_mut)]
+                let mut __sap_failures = ::std::vec::Vec::new();
+                #(#binds)*
+                #construct
+            }
+        }
+    })
+}
+
+fn component_id(field: &syn::Field) -> syn::Result<LitStr> {
+    let mut id = None;
+    for attr in field.attrs.iter().filter(|a| a.path().is_ident("sap")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("id") {
+                id = Some(meta.value()?.parse::<LitStr>()?);
+                Ok(())
+            } else {
+                Err(meta.error("unknown sap attribute, expected `id`"))
+            }
+        })?;
+    }
+    id.ok_or_else(|| {
+        syn::Error::new(
+            field.span(),
+            "missing component ID, add #[sap(id = \"...\")] to this field",
+        )
+    })
+}
@@ -0,0 +1,471 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::io::Read;
+use std::str::FromStr;
+
+use regex::Regex;
+
+use crate::Error;
+use crate::message::StatusMessage;
+
+/// Identifies a message by its class and number, e.g. `V1/001`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct MessageKey {
+    /// The message class (`ARBGB`), e.g. `V1`.
+    pub class: String,
+    /// The message number (`MSGNR`), e.g. `001`.
+    pub number: String,
+}
+
+impl MessageKey {
+    /// Create a key, normalising the class to upper case and the number to three digits.
+    pub fn new<C: AsRef<str>, N: AsRef<str>>(class: C, number: N) -> Self {
+        let number = number.as_ref().trim();
+        let number = match number.parse::<u16>() {
+            Ok(n) => format!("{n:03}"),
+            Err(_) => number.to_string(),
+        };
+        MessageKey {
+            class: class.as_ref().trim().to_uppercase(),
+            number,
+        }
+    }
+}
+
+impl fmt::Display for MessageKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.class, self.number)
+    }
+}
+
+impl FromStr for MessageKey {
+    type Err = String;
+
+    /// Parse a key written as `V1/001`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (class, number) = s
+            .rsplit_once('/')
+            .ok_or_else(|| format!("expected a message key like V1/001, got {s}"))?;
+        Ok(MessageKey::new(class, number))
+    }
+}
+
+impl StatusMessage {
+    /// The class and number of this message.
+    pub fn key(&self) -> MessageKey {
+        MessageKey::new(&self.class, &self.number)
+    }
+}
+
+/// A message text that was matched back to its catalog entry.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MessageMatch {
+    /// The message that was matched.
+    pub key: MessageKey,
+    /// The language of the text that matched.
+    pub language: String,
+    /// The parameters that were substituted into the text, indexed from `&1`.
+    pub parameters: Vec<String>,
+}
+
+#[derive(Clone, Debug)]
+struct CatalogText {
+    language: String,
+    template: String,
+    pattern: Regex,
+    /// The parameter index captured by each group of the pattern.
+    groups: Vec<usize>,
+    /// The number of literal characters, used to prefer the most specific match.
+    literal_len: usize,
+}
+
+/// A local copy of message texts (table `T100`), used to format messages and to recognise
+/// displayed texts regardless of the logon language.
+#[derive(Clone, Debug, Default)]
+pub struct MessageCatalog {
+    entries: HashMap<MessageKey, Vec<CatalogText>>,
+}
+
+impl MessageCatalog {
+    /// Create an empty catalog.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the text of a message in a language, replacing any text already present for it.
+    pub fn insert<L: Into<String>, T: Into<String>>(
+        &mut self,
+        key: MessageKey,
+        language: L,
+        template: T,
+    ) {
+        let language = language.into();
+        let template = template.into();
+        let (pattern, groups, literal_len) = compile(&template);
+        let texts = self.entries.entry(key).or_default();
+        texts.retain(|t| t.language != language);
+        texts.push(CatalogText {
+            language,
+            template,
+            pattern,
+            groups,
+            literal_len,
+        });
+    }
+
+    /// The number of messages in this catalog.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether this catalog has no messages.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Load a CSV export of `T100`. The first line must name the columns `SPRSL`, `ARBGB`,
+    /// `MSGNR` and `TEXT`, in any order, separated by commas, semicolons or tabs. A leading byte
+    /// order mark, as Excel and SAP GUI write, is ignored.
+    pub fn from_csv<R: Read>(mut reader: R) -> crate::Result<Self, Error> {
+        let mut data = String::new();
+        reader.read_to_string(&mut data)?;
+        let data = data.strip_prefix('\u{feff}').unwrap_or(&data);
+        let header = data.lines().next().unwrap_or_default();
+        let delimiter = [',', ';', '\t']
+            .into_iter()
+            .max_by_key(|d| header.matches(*d).count())
+            .unwrap_or(',');
+
+        let mut records = parse_csv(data, delimiter).into_iter();
+        let Some((_, header)) = records.next() else {
+            return Ok(Self::new());
+        };
+        let column = |name: &str| {
+            header
+                .iter()
+                .position(|h| h.trim().eq_ignore_ascii_case(name))
+                .ok_or_else(|| Error::Catalog {
+                    line: 1,
+                    message: format!("missing column {name}"),
+                })
+        };
+        let (language, class, number, text) = (
+            column("SPRSL")?,
+            column("ARBGB")?,
+            column("MSGNR")?,
+            column("TEXT")?,
+        );
+
+        let mut catalog = Self::new();
+        for (line, record) in records {
+            if record.iter().all(|f| f.trim().is_empty()) {
+                continue;
+            }
+            let field = |i: usize| {
+                record.get(i).ok_or_else(|| Error::Catalog {
+                    line,
+                    message: format!("expected at least {} fields", i + 1),
+                })
+            };
+            catalog.insert(
+                MessageKey::new(field(class)?, field(number)?),
+                field(language)?.trim(),
+                field(text)?.as_str(),
+            );
+        }
+        Ok(catalog)
+    }
+
+    /// Load a JSON export of `T100`: an array of objects with the fields `SPRSL`, `ARBGB`, `MSGNR`
+    /// and `TEXT`.
+    #[cfg(feature = "serde")]
+    pub fn from_json<R: Read>(reader: R) -> crate::Result<Self, Error> {
+        #[derive(serde::Deserialize)]
+        #[serde(rename_all = "UPPERCASE")]
+        struct Row {
+            #[serde(alias = "sprsl")]
+            sprsl: String,
+            #[serde(alias = "arbgb")]
+            arbgb: String,
+            #[serde(alias = "msgnr")]
+            msgnr: String,
+            #[serde(alias = "text")]
+            text: String,
+        }
+
+        let rows: Vec<Row> = serde_json::from_reader(reader).map_err(|e| Error::Catalog {
+            line: e.line(),
+            message: e.to_string(),
+        })?;
+        let mut catalog = Self::new();
+        for row in rows {
+            catalog.insert(MessageKey::new(row.arbgb, row.msgnr), row.sprsl, row.text);
+        }
+        Ok(catalog)
+    }
+
+    /// The text of a message in a language, with its placeholders.
+    pub fn template(&self, key: &MessageKey, language: &str) -> Option<&str> {
+        self.entries
+            .get(key)?
+            .iter()
+            .find(|t| t.language.eq_ignore_ascii_case(language))
+            .map(|t| t.template.as_str())
+    }
+
+    /// The text of a message in a language, with parameters substituted.
+    pub fn format<S: AsRef<str>>(
+        &self,
+        key: &MessageKey,
+        language: &str,
+        parameters: &[S],
+    ) -> Option<String> {
+        self.template(key, language)
+            .map(|template| substitute(template, parameters))
+    }
+
+    /// Find which message a displayed text came from, in any language, recovering its
+    /// parameters. When several messages match, the one with the most fixed text wins.
+    pub fn identify(&self, text: &str) -> Option<MessageMatch> {
+        let text = text.trim();
+        self.entries
+            .iter()
+            .flat_map(|(key, texts)| texts.iter().map(move |t| (key, t)))
+            .filter_map(|(key, t)| {
+                let captures = t.pattern.captures(text)?;
+                let mut parameters = vec![];
+                for (group, &index) in t.groups.iter().enumerate() {
+                    if parameters.len() <= index {
+                        parameters.resize(index + 1, String::new());
+                    }
+                    if let Some(m) = captures.get(group + 1) {
+                        parameters[index] = m.as_str().trim().to_string();
+                    }
+                }
+                Some((
+                    t.literal_len,
+                    MessageMatch {
+                        key: key.clone(),
+                        language: t.language.clone(),
+                        parameters,
+                    },
+                ))
+            })
+            .max_by(|(a_len, a), (b_len, b)| a_len.cmp(b_len).then_with(|| b.key.cmp(&a.key)))
+            .map(|(_, m)| m)
+    }
+}
+
+/// A part of a message template.
+enum Part<'a> {
+    Literal(&'a str),
+    /// A placeholder for the parameter with this index, counting from zero.
+    Placeholder(usize),
+}
+
+/// Split a template into literal text and placeholders. `&1` to `&4` refer to a parameter by
+/// number, a lone `&` takes the next parameter in order and `&&` is a literal ampersand.
+fn parts(template: &str) -> Vec<Part<'_>> {
+    let mut parts = vec![];
+    let mut next = 0;
+    let mut literal_start = 0;
+    let mut chars = template.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        if c != '&' {
+            continue;
+        }
+        if i > literal_start {
+            parts.push(Part::Literal(&template[literal_start..i]));
+        }
+        match chars.peek() {
+            Some(&(j, '&')) => {
+                chars.next();
+                parts.push(Part::Literal(&template[j..j + 1]));
+                literal_start = j + 1;
+            }
+            Some(&(j, d @ '1'..='4')) => {
+                chars.next();
+                parts.push(Part::Placeholder(d as usize - '1' as usize));
+                literal_start = j + 1;
+            }
+            _ => {
+                parts.push(Part::Placeholder(next));
+                next += 1;
+                literal_start = i + 1;
+            }
+        }
+    }
+    if literal_start < template.len() {
+        parts.push(Part::Literal(&template[literal_start..]));
+    }
+    parts
+}
+
+/// Substitute parameters into a message template. Missing parameters are left empty.
+pub fn substitute<S: AsRef<str>>(template: &str, parameters: &[S]) -> String {
+    parts(template)
+        .into_iter()
+        .map(|part| match part {
+            Part::Literal(text) => text,
+            Part::Placeholder(i) => parameters.get(i).map(AsRef::as_ref).unwrap_or_default(),
+        })
+        .collect()
+}
+
+/// Build a pattern matching the displayed texts of a template.
+fn compile(template: &str) -> (Regex, Vec<usize>, usize) {
+    let mut pattern = String::from("^");
+    let mut groups = vec![];
+    let mut literal_len = 0;
+    for part in parts(template.trim()) {
+        match part {
+            Part::Literal(text) => {
+                literal_len += text.chars().filter(|c| !c.is_whitespace()).count();
+                pattern.push_str(&regex::escape(text));
+            }
+            Part::Placeholder(i) => {
+                groups.push(i);
+                pattern.push_str("(.*?)");
+            }
+        }
+    }
+    pattern.push('$');
+    let pattern = Regex::new(&pattern).expect("escaped template is a valid pattern");
+    (pattern, groups, literal_len)
+}
+
+/// Parse CSV data as described by RFC 4180, returning each record with the line it starts on.
+fn parse_csv(data: &str, delimiter: char) -> Vec<(usize, Vec<String>)> {
+    let mut records = vec![];
+    let mut record = vec![];
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut line = 1;
+    let mut record_line = 1;
+    let mut chars = data.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes => {
+                if chars.peek() == Some(&'"') {
+                    chars.next();
+                    field.push('"');
+                } else {
+                    in_quotes = false;
+                }
+            }
+            '"' if field.is_empty() => in_quotes = true,
+            '\n' if in_quotes => {
+                line += 1;
+                field.push(c);
+            }
+            c if c == delimiter && !in_quotes => record.push(std::mem::take(&mut field)),
+            '\r' if !in_quotes && chars.peek() == Some(&'\n') => (),
+            '\n' => {
+                record.push(std::mem::take(&mut field));
+                records.push((record_line, std::mem::take(&mut record)));
+                line += 1;
+                record_line = line;
+            }
+            c => field.push(c),
+        }
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push((record_line, record));
+    }
+    records
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn catalog(entries: &[(&str, &str, &str, &str)]) -> MessageCatalog {
+        let mut catalog = MessageCatalog::new();
+        for (class, number, language, text) in entries {
+            catalog.insert(MessageKey::new(class, number), *language, *text);
+        }
+        catalog
+    }
+
+    #[test]
+    fn message_key_is_normalised() {
+        assert_eq!(MessageKey::new(" v1 ", "1"), MessageKey::new("V1", "001"));
+        assert_eq!("V1/12".parse(), Ok(MessageKey::new("V1", "012")));
+        assert!("V1".parse::<MessageKey>().is_err());
+    }
+
+    #[test]
+    fn substitutes_placeholders() {
+        assert_eq!(substitute("Order & saved", &["4711"]), "Order 4711 saved");
+        assert_eq!(substitute("& & &", &["a", "b", "c"]), "a b c");
+        assert_eq!(substitute("&2 before &1", &["a", "b"]), "b before a");
+        assert_eq!(substitute("A && B", &["x"]), "A & B");
+        assert_eq!(substitute("Missing &3", &["a"]), "Missing ");
+        assert_eq!(substitute("Trailing &", &["end"]), "Trailing end");
+    }
+
+    #[test]
+    fn identifies_messages_with_parameters() {
+        let catalog = catalog(&[
+            ("V1", "311", "EN", "&1 &2 has been saved"),
+            ("V1", "311", "DE", "&1 &2 wurde gesichert"),
+        ]);
+        let found = catalog
+            .identify("Standard Order 4711 wurde gesichert")
+            .unwrap();
+        assert_eq!(found.key, MessageKey::new("V1", "311"));
+        assert_eq!(found.language, "DE");
+        assert_eq!(found.parameters.join(" "), "Standard Order 4711");
+        assert!(catalog.identify("Something else entirely").is_none());
+    }
+
+    #[test]
+    fn identify_prefers_the_most_specific_text() {
+        let catalog = catalog(&[
+            ("00", "001", "EN", "&1&2&3&4"),
+            ("F5", "312", "EN", "Document & was posted in company code &"),
+        ]);
+        let found = catalog
+            .identify("Document 100000 was posted in company code 1000")
+            .unwrap();
+        assert_eq!(found.key, MessageKey::new("F5", "312"));
+        assert_eq!(found.parameters, ["100000", "1000"]);
+    }
+
+    #[test]
+    fn parses_quoted_csv() {
+        let records = parse_csv("a,\"b,c\",\"say \"\"hi\"\"\"\r\n\"multi\nline\",x\n", ',');
+        assert_eq!(
+            records,
+            [
+                (
+                    1,
+                    vec!["a".to_string(), "b,c".to_string(), "say \"hi\"".to_string()]
+                ),
+                (2, vec!["multi\nline".to_string(), "x".to_string()]),
+            ]
+        );
+        assert_eq!(
+            parse_csv("a;b", ';'),
+            [(1, vec!["a".to_string(), "b".to_string()])]
+        );
+    }
+
+    #[test]
+    fn loads_csv_with_byte_order_mark() {
+        let data = "\u{feff}SPRSL;ARBGB;MSGNR;TEXT\r\nE;V1;1;Order & saved\r\n;;;\r\n";
+        let catalog = MessageCatalog::from_csv(data.as_bytes()).unwrap();
+        assert_eq!(catalog.len(), 1);
+        assert_eq!(
+            catalog.template(&MessageKey::new("V1", "001"), "E"),
+            Some("Order & saved")
+        );
+    }
+
+    #[test]
+    fn reports_missing_columns() {
+        let error = MessageCatalog::from_csv("SPRSL,ARBGB,TEXT\n".as_bytes()).unwrap_err();
+        assert!(matches!(error, Error::Catalog { line: 1, .. }));
+    }
+}
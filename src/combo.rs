@@ -0,0 +1,78 @@
+use com_shim::HasIDispatch;
+
+use crate::Error;
+use crate::text_match::TextMatch;
+use crate::types::*;
+
+/// An entry of a combo box.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ComboOption {
+    /// The key of the entry, which is what gets stored.
+    pub key: String,
+    /// The text of the entry, as displayed.
+    pub value: String,
+    /// The position of the entry in the list, as reported by SAP GUI.
+    pub pos: i32,
+}
+
+/// Read the entries of a combo box.
+fn read_options(entries: GuiCollection) -> crate::Result<Vec<ComboOption>> {
+    entries
+        .components()?
+        .into_iter()
+        .map(|entry| {
+            let entry = GuiComboBoxEntry::from(entry.get_idispatch().clone());
+            Ok(ComboOption {
+                key: entry.key()?,
+                value: entry.value()?,
+                pos: entry.pos()?,
+            })
+        })
+        .collect()
+}
+
+/// Find the option whose text matches, or fail listing the options there are.
+fn find_option(options: Vec<ComboOption>, text: TextMatch) -> crate::Result<ComboOption, Error> {
+    let Some(index) = options.iter().position(|o| text.matches(&o.value)) else {
+        return Err(Error::NoSuchOption {
+            wanted: text.to_string(),
+            options: options.into_iter().map(|o| o.value).collect(),
+        });
+    };
+    Ok(options.into_iter().nth(index).expect("index was found"))
+}
+
+impl GuiComboBox {
+    /// The entries of this combo box.
+    pub fn options(&self) -> crate::Result<Vec<ComboOption>> {
+        read_options(self.entries()?)
+    }
+
+    /// Select the first entry whose displayed text matches, returning it. Plain text must match
+    /// exactly; use [`TextMatch::CaseInsensitive`] to ignore case.
+    pub fn select_by_text<M: Into<TextMatch>>(&self, text: M) -> crate::Result<ComboOption, Error> {
+        let option = find_option(self.options()?, text.into())?;
+        if option.key.trim().is_empty() {
+            self.set_key_space()?;
+        } else {
+            self.set_key(option.key.clone())?;
+        }
+        Ok(option)
+    }
+}
+
+impl GuiComboBoxControl {
+    /// The entries of this combo box.
+    pub fn options(&self) -> crate::Result<Vec<ComboOption>> {
+        read_options(self.entries()?)
+    }
+
+    /// Select the first entry whose displayed text matches and notify the application, returning
+    /// the entry. Plain text must match exactly; use [`TextMatch::CaseInsensitive`] to ignore case.
+    pub fn select_by_text<M: Into<TextMatch>>(&self, text: M) -> crate::Result<ComboOption, Error> {
+        let option = find_option(self.options()?, text.into())?;
+        self.set_selected(option.key.clone())?;
+        self.fire_selected()?;
+        Ok(option)
+    }
+}
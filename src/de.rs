@@ -0,0 +1,565 @@
+use std::fmt;
+
+use serde::de::{self, DeserializeOwned, IntoDeserializer, Visitor};
+
+use crate::Error;
+use crate::format::FormatProfile;
+use crate::table::*;
+use crate::types::*;
+
+impl Table {
+    /// Deserialize each row into a struct, skipping total and subtotal rows.
+    ///
+    /// Fields are matched to columns by technical name, or failing that by displayed title,
+    /// ignoring case. `#[serde(rename = "...")]` can be used to name the column of a field. Cells
+    /// are converted to whatever type the field has: numbers in display format such as
+    /// `1.234,56-` are parsed, check box cells become booleans and date cells are passed on in
+    /// ISO 8601 format (`2024-12-31`), which date types such as `chrono::NaiveDate` accept.
+    ///
+    /// As the display format is not known, numbers and dates are read leniently. A number with a
+    /// single separator followed by exactly three digits, such as `1.250`, could be a decimal or a
+    /// thousands separator, so it fails rather than being guessed. Use
+    /// [`Table::deserialize_rows_with`] to read numbers in a known format instead.
+    pub fn deserialize_rows<T: DeserializeOwned>(&self) -> crate::Result<Vec<T>, Error> {
+        self.deserialize(None)
+    }
+
+    /// Deserialize each row into a struct, like [`Table::deserialize_rows`], reading numbers,
+    /// dates and times in the display format of a profile. Time cells are passed on in ISO 8601
+    /// format (`23:59:59`).
+    pub fn deserialize_rows_with<T: DeserializeOwned>(
+        &self,
+        profile: &FormatProfile,
+    ) -> crate::Result<Vec<T>, Error> {
+        self.deserialize(Some(profile))
+    }
+
+    fn deserialize<T: DeserializeOwned>(
+        &self,
+        profile: Option<&FormatProfile>,
+    ) -> crate::Result<Vec<T>, Error> {
+        self.rows
+            .iter()
+            .enumerate()
+            .filter(|(_, row)| !row.is_total())
+            .map(|(index, row)| {
+                T::deserialize(RowDeserializer {
+                    columns: &self.columns,
+                    row,
+                    index,
+                    profile,
+                })
+                .map_err(|e| Error::Deserialize {
+                    row: e.row.or(Some(index)),
+                    column: e.column,
+                    message: e.message,
+                })
+            })
+            .collect()
+    }
+}
+
+impl GuiGridView {
+    /// Extract every row of this grid and deserialize it into a struct.
+    /// See [`Table::deserialize_rows`] for how cells are matched and converted.
+    pub fn rows<T: DeserializeOwned>(&self) -> crate::Result<Vec<T>, Error> {
+        self.extract()?.deserialize_rows()
+    }
+
+    /// Extract every row of this grid and deserialize it into a struct, reading values in the
+    /// display format of a profile. See [`Table::deserialize_rows_with`].
+    pub fn rows_with<T: DeserializeOwned>(
+        &self,
+        profile: &FormatProfile,
+    ) -> crate::Result<Vec<T>, Error> {
+        self.extract()?.deserialize_rows_with(profile)
+    }
+}
+
+impl GuiTableControl {
+    /// Extract every row of this table control and deserialize it into a struct.
+    /// See [`Table::deserialize_rows`] for how cells are matched and converted.
+    pub fn rows<T: DeserializeOwned>(&self) -> crate::Result<Vec<T>, Error> {
+        self.extract()?.deserialize_rows()
+    }
+
+    /// Extract every row of this table control and deserialize it into a struct, reading values
+    /// in the display format of a profile. See [`Table::deserialize_rows_with`].
+    pub fn rows_with<T: DeserializeOwned>(
+        &self,
+        profile: &FormatProfile,
+    ) -> crate::Result<Vec<T>, Error> {
+        self.extract()?.deserialize_rows_with(profile)
+    }
+}
+
+/// An error deserializing a row, with as much context as is known.
+#[derive(Debug)]
+struct DeError {
+    row: Option<usize>,
+    column: Option<String>,
+    message: String,
+}
+
+impl DeError {
+    fn at(mut self, row: usize, column: &Column) -> Self {
+        self.row.get_or_insert(row);
+        self.column.get_or_insert_with(|| column.name.clone());
+        self
+    }
+}
+
+impl fmt::Display for DeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for DeError {}
+
+impl de::Error for DeError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        DeError {
+            row: None,
+            column: None,
+            message: msg.to_string(),
+        }
+    }
+}
+
+struct RowDeserializer<'a> {
+    columns: &'a [Column],
+    row: &'a Row,
+    index: usize,
+    profile: Option<&'a FormatProfile>,
+}
+
+impl<'a> RowDeserializer<'a> {
+    fn cells(&self, columns: Vec<(&'a str, usize)>) -> RowCells<'a> {
+        RowCells {
+            columns: self.columns,
+            row: self.row,
+            index: self.index,
+            profile: self.profile,
+            fields: columns.into_iter(),
+            current: None,
+        }
+    }
+}
+
+impl<'de, 'a> de::Deserializer<'de> for RowDeserializer<'a> {
+    type Error = DeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        let columns = self
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(i, c)| (c.name.as_str(), i))
+            .collect();
+        visitor.visit_map(self.cells(columns))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        let by_name = |field: &str| {
+            self.columns
+                .iter()
+                .position(|c| c.name.eq_ignore_ascii_case(field))
+        };
+        let by_title = |field: &str| {
+            self.columns
+                .iter()
+                .position(|c| c.title.trim().eq_ignore_ascii_case(field))
+        };
+        let columns = fields
+            .iter()
+            .filter_map(|&field| {
+                by_name(field)
+                    .or_else(|| by_title(field))
+                    .map(|i| (field, i))
+            })
+            .collect();
+        visitor.visit_map(self.cells(columns))
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map enum identifier ignored_any
+    }
+}
+
+struct RowCells<'a> {
+    columns: &'a [Column],
+    row: &'a Row,
+    index: usize,
+    profile: Option<&'a FormatProfile>,
+    fields: std::vec::IntoIter<(&'a str, usize)>,
+    current: Option<usize>,
+}
+
+impl<'de, 'a> de::MapAccess<'de> for RowCells<'a> {
+    type Error = DeError;
+
+    fn next_key_seed<K: de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Self::Error> {
+        let Some((field, column)) = self.fields.next() else {
+            return Ok(None);
+        };
+        self.current = Some(column);
+        seed.deserialize(field.into_deserializer()).map(Some)
+    }
+
+    fn next_value_seed<V: de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, Self::Error> {
+        let column = self.current.take().expect("key is read before its value");
+        let cell = CellDeserializer {
+            column: &self.columns[column],
+            profile: self.profile,
+            value: self.row.cells.get(column).map(String::as_str).unwrap_or(""),
+        };
+        seed.deserialize(cell)
+            .map_err(|e| e.at(self.index, &self.columns[column]))
+    }
+}
+
+struct CellDeserializer<'a> {
+    column: &'a Column,
+    profile: Option<&'a FormatProfile>,
+    value: &'a str,
+}
+
+impl CellDeserializer<'_> {
+    fn invalid(&self, expected: &str) -> DeError {
+        de::Error::custom(format!("expected {expected}, found \"{}\"", self.value))
+    }
+
+    fn number(&self) -> Result<String, DeError> {
+        match self.profile {
+            Some(profile) => profile
+                .parse_decimal(self.value)
+                .map(|n| n.to_string())
+                .map_err(de::Error::custom),
+            None => parse_number(self.value, &self.column.data_type).map_err(|problem| match problem {
+                NumberProblem::Invalid => self.invalid("a number"),
+                NumberProblem::Ambiguous => de::Error::custom(format!(
+                    "\"{}\" could have a decimal or a thousands separator, read it with a format profile",
+                    self.value
+                )),
+            }),
+        }
+    }
+
+    /// The value of a date or time cell in ISO 8601 format, if it is one.
+    fn iso(&self) -> Result<Option<String>, DeError> {
+        let value = self.value.trim();
+        if value.is_empty() {
+            return Ok(None);
+        }
+        Ok(match (&self.column.data_type, self.profile) {
+            (DataType::Date, Some(profile)) => Some(
+                profile
+                    .parse_date(value)
+                    .map_err(de::Error::custom)?
+                    .to_string(),
+            ),
+            (DataType::Date, None) => {
+                Some(parse_date(value).ok_or_else(|| self.invalid("a date"))?)
+            }
+            (DataType::Time, Some(profile)) => Some(
+                profile
+                    .parse_time(value)
+                    .map_err(de::Error::custom)?
+                    .format("%H:%M:%S")
+                    .to_string(),
+            ),
+            _ => None,
+        })
+    }
+
+    fn bool(&self) -> Result<bool, DeError> {
+        match self.value.trim().to_ascii_lowercase().as_str() {
+            "x" | "true" | "1" | "yes" => Ok(true),
+            "" | "false" | "0" | "no" | "-" => Ok(false),
+            _ => Err(self.invalid("a check box value")),
+        }
+    }
+}
+
+macro_rules! deserialize_number {
+    ($($method: ident => $visit: ident: $ty: ty),*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+                let number = self.number()?;
+                let expected = concat!("a number of type ", stringify!($ty));
+                visitor.$visit(number.parse::<$ty>().map_err(|_| self.invalid(expected))?)
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for CellDeserializer<'_> {
+    type Error = DeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        if self.column.checkbox {
+            return self.deserialize_bool(visitor);
+        }
+        match self.column.data_type {
+            DataType::Integer if !self.value.trim().is_empty() => self.deserialize_i64(visitor),
+            DataType::Packed | DataType::Float if !self.value.trim().is_empty() => {
+                self.deserialize_f64(visitor)
+            }
+            _ => self.deserialize_str(visitor),
+        }
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_bool(self.bool()?)
+    }
+
+    deserialize_number! {
+        deserialize_i8 => visit_i8: i8,
+        deserialize_i16 => visit_i16: i16,
+        deserialize_i32 => visit_i32: i32,
+        deserialize_i64 => visit_i64: i64,
+        deserialize_i128 => visit_i128: i128,
+        deserialize_u8 => visit_u8: u8,
+        deserialize_u16 => visit_u16: u16,
+        deserialize_u32 => visit_u32: u32,
+        deserialize_u64 => visit_u64: u64,
+        deserialize_u128 => visit_u128: u128,
+        deserialize_f32 => visit_f32: f32,
+        deserialize_f64 => visit_f64: f64
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.iso()? {
+            Some(iso) => visitor.visit_string(iso),
+            None => visitor.visit_str(self.value.trim()),
+        }
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_char<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        if self.value.trim().is_empty() && !self.column.checkbox {
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_enum(self.value.trim().into_deserializer())
+    }
+
+    serde::forward_to_deserialize_any! {
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct
+        identifier ignored_any
+    }
+}
+
+/// Why a number in an unknown display format could not be read.
+#[derive(Debug, PartialEq, Eq)]
+enum NumberProblem {
+    /// The text is not a number.
+    Invalid,
+    /// The text has a single separator with one to three digits before it and exactly three
+    /// after it, which could be either a decimal or a thousands separator.
+    Ambiguous,
+}
+
+/// Convert a number in display format to the standard format, e.g. `1.234,56-` to `-1234.56`.
+///
+/// In an integer or numeric text column every separator groups thousands. Otherwise the decimal
+/// separator is taken to be the last `.` or `,`, unless it appears more than once, in which case it
+/// separates thousands. A single separator with one to three digits other than `0` before it and
+/// exactly three after it is ambiguous.
+fn parse_number(text: &str, data_type: &DataType) -> Result<String, NumberProblem> {
+    let mut text: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+    if text.is_empty() {
+        return Err(NumberProblem::Invalid);
+    }
+    let negative = if let Some(rest) = text.strip_suffix('-') {
+        text = rest.to_string();
+        true
+    } else if let Some(rest) = text.strip_prefix('-') {
+        text = rest.to_string();
+        true
+    } else {
+        false
+    };
+
+    let grouped = matches!(data_type, DataType::Integer | DataType::Numc);
+    let last_separator = text.rfind(['.', ',']).filter(|_| !grouped);
+    if let Some(i) = last_separator {
+        let separator = &text[i..i + 1];
+        let other = if separator == "." { ',' } else { '.' };
+        let single = text.matches(separator).count() == 1 && !text.contains(other);
+        let three_digits = text.len() - i - 1 == 3;
+        let whole = &text[..i];
+        let short_whole =
+            (1..=3).contains(&whole.len()) && whole.bytes().all(|b| b.is_ascii_digit());
+        if single && three_digits && short_whole && whole != "0" {
+            return Err(NumberProblem::Ambiguous);
+        }
+    }
+    let decimal = last_separator.filter(|&i| text.matches(&text[i..i + 1]).count() == 1);
+    let mut number = String::with_capacity(text.len() + 1);
+    if negative {
+        number.push('-');
+    }
+    for (i, c) in text.char_indices() {
+        match c {
+            '0'..='9' => number.push(c),
+            '.' | ',' if Some(i) == decimal => number.push('.'),
+            '.' | ',' | '\'' => (),
+            _ => return Err(NumberProblem::Invalid),
+        }
+    }
+    Ok(number)
+}
+
+/// Convert a date in any SAP display format to ISO 8601, e.g. `31.12.2024` to `2024-12-31`.
+fn parse_date(text: &str) -> Option<String> {
+    let parts: Vec<&str> = text.split(['.', '/', '-']).collect();
+    let [a, b, c] = parts[..] else {
+        return None;
+    };
+    let (year, month, day) = if a.len() == 4 {
+        (a, b, c)
+    } else if text.contains('.') {
+        (c, b, a)
+    } else {
+        (c, a, b)
+    };
+    let year: u32 = year.parse().ok()?;
+    let month: u32 = month.parse().ok()?;
+    let day: u32 = day.parse().ok()?;
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    Some(format!("{year:04}-{month:02}-{day:02}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_numbers_in_either_notation() {
+        assert_eq!(
+            parse_number("1.234,56-", &DataType::Packed),
+            Ok("-1234.56".to_string())
+        );
+        assert_eq!(
+            parse_number("1,234.56", &DataType::Packed),
+            Ok("1234.56".to_string())
+        );
+        assert_eq!(
+            parse_number("-1 234,5", &DataType::Packed),
+            Ok("-1234.5".to_string())
+        );
+        assert_eq!(
+            parse_number("1.234.567", &DataType::Packed),
+            Ok("1234567".to_string())
+        );
+        assert_eq!(
+            parse_number("12,5", &DataType::Packed),
+            Ok("12.5".to_string())
+        );
+        assert_eq!(
+            parse_number("0,125", &DataType::Packed),
+            Ok("0.125".to_string())
+        );
+        assert_eq!(
+            parse_number("abc", &DataType::Packed),
+            Err(NumberProblem::Invalid)
+        );
+        assert_eq!(
+            parse_number("", &DataType::Packed),
+            Err(NumberProblem::Invalid)
+        );
+    }
+
+    #[test]
+    fn rejects_ambiguous_numbers() {
+        assert_eq!(
+            parse_number("1.250", &DataType::Packed),
+            Err(NumberProblem::Ambiguous)
+        );
+        assert_eq!(
+            parse_number("1,250", &DataType::Packed),
+            Err(NumberProblem::Ambiguous)
+        );
+        assert_eq!(
+            parse_number("-12.500", &DataType::Packed),
+            Err(NumberProblem::Ambiguous)
+        );
+        assert_eq!(
+            parse_number("1234.567", &DataType::Packed),
+            Ok("1234.567".to_string())
+        );
+        assert_eq!(
+            parse_number("1'234.567", &DataType::Packed),
+            Ok("1234.567".to_string())
+        );
+    }
+
+    #[test]
+    fn groups_thousands_in_integer_columns() {
+        assert_eq!(
+            parse_number("1.500", &DataType::Integer),
+            Ok("1500".to_string())
+        );
+        assert_eq!(
+            parse_number("1,500-", &DataType::Integer),
+            Ok("-1500".to_string())
+        );
+        assert_eq!(
+            parse_number("1.500", &DataType::Numc),
+            Ok("1500".to_string())
+        );
+        assert_eq!(
+            parse_number("12.345.678", &DataType::Numc),
+            Ok("12345678".to_string())
+        );
+    }
+
+    #[test]
+    fn parses_dates_in_any_format() {
+        assert_eq!(parse_date("31.12.2024").as_deref(), Some("2024-12-31"));
+        assert_eq!(parse_date("12/31/2024").as_deref(), Some("2024-12-31"));
+        assert_eq!(parse_date("2024-12-31").as_deref(), Some("2024-12-31"));
+        assert_eq!(parse_date("31.13.2024"), None);
+    }
+}
@@ -0,0 +1,222 @@
+use std::fmt;
+
+use crate::message::StatusMessage;
+use crate::page::BindFailure;
+use crate::screen::ScreenFingerprint;
+use crate::value::FieldProblem;
+
+/// An error from one of the higher-level helpers in this library.
+#[derive(Debug)]
+pub enum Error {
+    /// A call to the SAP scripting API failed.
+    Com(windows::core::Error),
+    /// A screen flow reached a screen that it has no handler for.
+    UnknownScreen {
+        /// The screen that was reached.
+        fingerprint: Box<ScreenFingerprint>,
+        /// The object tree of the active window, as reported by `GuiSession::GetObjectTree`.
+        dump: String,
+    },
+    /// A screen flow handled more screens than it was allowed to without finishing.
+    StepLimit {
+        /// The maximum number of screens the flow could handle.
+        limit: usize,
+        /// The screen that was reached when the limit was hit.
+        fingerprint: Box<ScreenFingerprint>,
+    },
+    /// One or more components of a [`SapScreen`](crate::SapScreen) could not be bound.
+    Bind(Vec<BindFailure>),
+    /// A popup was open that could not be dealt with.
+    Popup {
+        /// The title of the popup window.
+        title: String,
+        /// The text of the popup.
+        text: String,
+    },
+    /// The status bar showed a message that is not acceptable after an action.
+    Message(Box<StatusMessage>),
+    /// Reading or writing data failed.
+    Io(std::io::Error),
+    /// A message catalog could not be loaded.
+    Catalog {
+        /// The line of the input the problem was found on.
+        line: usize,
+        /// What was wrong.
+        message: String,
+    },
+    /// A value was not in the expected display format.
+    Format(crate::format::FormatError),
+    /// A row could not be deserialized.
+    Deserialize {
+        /// The index of the row, if known.
+        row: Option<usize>,
+        /// The technical name of the column, if known.
+        column: Option<String>,
+        /// What was wrong.
+        message: String,
+    },
+    /// No tree node matched a segment of a text path.
+    TreePath {
+        /// The index of the segment in the path, counting from zero.
+        index: usize,
+        /// The segment that nothing matched.
+        segment: String,
+    },
+    /// A value could not be read from or written to a field.
+    Field {
+        /// The ID of the field.
+        id: String,
+        /// What was wrong.
+        problem: FieldProblem,
+    },
+    /// No entry of a combo box matched.
+    NoSuchOption {
+        /// What an entry was wanted to match.
+        wanted: String,
+        /// The texts of the entries there are.
+        options: Vec<String>,
+    },
+    /// A grid or table control has no column with the name given.
+    NoSuchColumn {
+        /// The name that was looked for.
+        column: String,
+    },
+    /// A row is outside a grid or table control.
+    RowOutOfRange {
+        /// The row, counting from zero.
+        row: i32,
+        /// The number of rows there are.
+        row_count: i32,
+    },
+    /// Appending rows to a table control failed partway.
+    Append {
+        /// The absolute row the first appended row was written to.
+        first_row: i32,
+        /// The number of rows fully written before the failure.
+        written: usize,
+        /// Why the next row could not be written.
+        source: Box<Error>,
+    },
+    /// Editing rows of a grid failed partway.
+    GridEdit {
+        /// The row the first edit was written to.
+        first_row: i32,
+        /// The number of rows fully written before the failure.
+        written: usize,
+        /// The number of rows that were appended to the grid before writing.
+        inserted: i32,
+        /// Why the next row could not be written.
+        source: Box<Error>,
+    },
+    /// Values could not be serialized into form fields.
+    Serialize {
+        /// What was wrong.
+        message: String,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Com(e) => write!(f, "scripting call failed: {e}"),
+            Error::UnknownScreen { fingerprint, .. } => {
+                write!(f, "no handler for screen {fingerprint}")
+            }
+            Error::StepLimit { limit, fingerprint } => write!(
+                f,
+                "screen flow did not finish within {limit} steps, last screen was {fingerprint}"
+            ),
+            Error::Bind(failures) => {
+                write!(f, "failed to bind {} component(s)", failures.len())?;
+                for failure in failures {
+                    write!(f, "\n  {failure}")?;
+                }
+                Ok(())
+            }
+            Error::Popup { title, text } => write!(f, "blocked by popup \"{title}\": {text}"),
+            Error::Message(message) => write!(f, "{message}"),
+            Error::Io(e) => write!(f, "i/o error: {e}"),
+            Error::Catalog { line, message } => {
+                write!(f, "invalid message catalog on line {line}: {message}")
+            }
+            Error::Format(e) => write!(f, "invalid value: {e}"),
+            Error::TreePath { index, segment } => write!(
+                f,
+                "no tree node matches segment {} of the path, {segment}",
+                index + 1
+            ),
+            Error::Field { id, problem } => write!(f, "field {id}: {problem}"),
+            Error::NoSuchOption { wanted, options } => write!(
+                f,
+                "no option matches {wanted}, the options are \"{}\"",
+                options.join("\", \"")
+            ),
+            Error::NoSuchColumn { column } => write!(f, "no column {column}"),
+            Error::RowOutOfRange { row, row_count } => {
+                write!(f, "row {row} is out of range, there are {row_count} rows")
+            }
+            Error::Append {
+                first_row,
+                written,
+                source,
+            } => write!(
+                f,
+                "appended {written} row(s) from row {first_row} before failing: {source}"
+            ),
+            Error::GridEdit {
+                first_row,
+                written,
+                inserted,
+                source,
+            } => write!(
+                f,
+                "edited {written} grid row(s) from row {first_row}, after appending {inserted}, before failing: {source}"
+            ),
+            Error::Serialize { message } => write!(f, "failed to serialize form values: {message}"),
+            Error::Deserialize {
+                row,
+                column,
+                message,
+            } => {
+                write!(f, "failed to deserialize")?;
+                if let Some(row) = row {
+                    write!(f, " row {row}")?;
+                }
+                if let Some(column) = column {
+                    write!(f, " column {column}")?;
+                }
+                write!(f, ": {message}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Com(e) => Some(e),
+            Error::Io(e) => Some(e),
+            Error::Format(e) => Some(e),
+            Error::Append { source, .. } | Error::GridEdit { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl From<windows::core::Error> for Error {
+    fn from(value: windows::core::Error) -> Self {
+        Error::Com(value)
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(value: std::io::Error) -> Self {
+        Error::Io(value)
+    }
+}
+
+impl From<crate::format::FormatError> for Error {
+    fn from(value: crate::format::FormatError) -> Self {
+        Error::Format(value)
+    }
+}
@@ -0,0 +1,517 @@
+use std::io::{self, Write};
+
+use crate::Error;
+use crate::format::FormatProfile;
+use crate::table::*;
+use crate::types::*;
+
+/// How columns are named in exported headers and JSON keys.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum HeaderStyle {
+    /// The technical name of each column, e.g. `MATNR`.
+    #[default]
+    Technical,
+    /// The title of each column, as displayed. A column whose title is blank or shared with another
+    /// column is named by its title followed by its technical name, e.g. `Amount (WRBTR)`, so that
+    /// every column has a distinct name.
+    Title,
+}
+
+/// A file format tables can be exported to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ExportFormat {
+    /// Comma separated values as described by RFC 4180, with the delimiter from the options.
+    Csv,
+    /// One JSON object per line, keyed by column.
+    JsonLines,
+    /// Tab separated values that keep their meaning when pasted into Excel.
+    ExcelTsv,
+}
+
+/// Options for exporting a [`Table`].
+#[derive(Clone, Debug)]
+pub struct ExportOptions {
+    header: HeaderStyle,
+    include_header: bool,
+    delimiter: char,
+    numbers: Option<FormatProfile>,
+    skip_totals: bool,
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        ExportOptions {
+            header: HeaderStyle::Technical,
+            include_header: true,
+            delimiter: ',',
+            numbers: None,
+            skip_totals: false,
+        }
+    }
+}
+
+impl ExportOptions {
+    /// Create the default options: a header row of technical names, comma delimited, with
+    /// values exactly as displayed.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set how columns are named.
+    pub fn header(mut self, style: HeaderStyle) -> Self {
+        self.header = style;
+        self
+    }
+
+    /// Set whether CSV and TSV output start with a header row.
+    pub fn include_header(mut self, include: bool) -> Self {
+        self.include_header = include;
+        self
+    }
+
+    /// Set the delimiter of CSV output, e.g. `;` for spreadsheets in locales using decimal commas.
+    pub fn delimiter(mut self, delimiter: char) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Read the values of numeric columns in the display format of a profile and write them in
+    /// the standard format instead, e.g. `1.234,56-` as `-1234.56`. JSON Lines output then has
+    /// numbers rather than strings for them. Values that cannot be read are written as displayed.
+    pub fn normalise_numbers(mut self, profile: FormatProfile) -> Self {
+        self.numbers = Some(profile);
+        self
+    }
+
+    /// Set whether total and subtotal rows are left out.
+    pub fn skip_totals(mut self, skip: bool) -> Self {
+        self.skip_totals = skip;
+        self
+    }
+}
+
+/// Writes rows to an export one at a time, so a large report need not be held in memory twice.
+/// The header is written when the writer is created.
+pub struct RowWriter<'a, W: Write> {
+    writer: W,
+    columns: &'a [Column],
+    names: Vec<String>,
+    format: ExportFormat,
+    options: &'a ExportOptions,
+}
+
+impl<'a, W: Write> RowWriter<'a, W> {
+    /// Start an export of rows with the given columns.
+    pub fn new(
+        mut writer: W,
+        columns: &'a [Column],
+        format: ExportFormat,
+        options: &'a ExportOptions,
+    ) -> io::Result<Self> {
+        let names = options.column_names(columns);
+        if options.include_header {
+            match format {
+                ExportFormat::Csv => write_csv_record(&mut writer, &names, options.delimiter)?,
+                ExportFormat::ExcelTsv => {
+                    let titles: Vec<String> =
+                        names.iter().map(|name| excel_text(name, false)).collect();
+                    write_tsv_record(&mut writer, &titles)?
+                }
+                ExportFormat::JsonLines => {}
+            }
+        }
+        Ok(RowWriter {
+            writer,
+            columns,
+            names,
+            format,
+            options,
+        })
+    }
+
+    /// Write a row, unless it is a total and totals are skipped.
+    pub fn write_row(&mut self, row: &Row) -> io::Result<()> {
+        if self.options.skip_totals && row.is_total() {
+            return Ok(());
+        }
+        let values: Vec<Value<'_>> = self
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(i, column)| {
+                let text = row.cells.get(i).map(String::as_str).unwrap_or_default();
+                self.options.value(column, text)
+            })
+            .collect();
+
+        match self.format {
+            ExportFormat::Csv => {
+                let fields: Vec<&str> = values.iter().map(Value::as_str).collect();
+                write_csv_record(&mut self.writer, &fields, self.options.delimiter)
+            }
+            ExportFormat::ExcelTsv => {
+                let fields: Vec<String> = self
+                    .columns
+                    .iter()
+                    .zip(&values)
+                    .map(|(column, value)| excel_field(column, value))
+                    .collect();
+                write_tsv_record(&mut self.writer, &fields)
+            }
+            ExportFormat::JsonLines => {
+                let mut line = String::from("{");
+                for (i, (name, value)) in self.names.iter().zip(&values).enumerate() {
+                    if i > 0 {
+                        line.push(',');
+                    }
+                    push_json_string(&mut line, name);
+                    line.push(':');
+                    match value {
+                        Value::Number(n) => line.push_str(n),
+                        Value::Text(t) => push_json_string(&mut line, t),
+                    }
+                }
+                line.push_str("}\n");
+                self.writer.write_all(line.as_bytes())
+            }
+        }
+    }
+
+    /// Flush the export and return the underlying writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+impl Table {
+    /// Write this table in a format.
+    pub fn export<W: Write>(
+        &self,
+        writer: W,
+        format: ExportFormat,
+        options: &ExportOptions,
+    ) -> io::Result<W> {
+        let mut rows = RowWriter::new(writer, &self.columns, format, options)?;
+        for row in &self.rows {
+            rows.write_row(row)?;
+        }
+        rows.finish()
+    }
+
+    /// Write this table as CSV, as described by RFC 4180.
+    pub fn write_csv<W: Write>(&self, writer: W, options: &ExportOptions) -> io::Result<W> {
+        self.export(writer, ExportFormat::Csv, options)
+    }
+
+    /// Write this table as JSON Lines, one object per row.
+    pub fn write_json_lines<W: Write>(&self, writer: W, options: &ExportOptions) -> io::Result<W> {
+        self.export(writer, ExportFormat::JsonLines, options)
+    }
+
+    /// Write this table as tab separated values that can be pasted into Excel.
+    pub fn write_excel_tsv<W: Write>(&self, writer: W, options: &ExportOptions) -> io::Result<W> {
+        self.export(writer, ExportFormat::ExcelTsv, options)
+    }
+}
+
+impl GuiGridView {
+    /// Export every row of this grid as it is read, without holding the whole grid in memory.
+    /// The grid is paged through as in [`GuiGridView::extract`].
+    pub fn export<W: Write>(
+        &self,
+        writer: W,
+        format: ExportFormat,
+        options: &ExportOptions,
+    ) -> crate::Result<W, Error> {
+        let columns = self.column_info()?;
+        let mut rows = RowWriter::new(writer, &columns, format, options)?;
+        self.read_rows(&columns, |row| rows.write_row(&row).map_err(Error::from))?;
+        Ok(rows.finish()?)
+    }
+}
+
+/// An exported cell value.
+enum Value<'a> {
+    Text(&'a str),
+    /// A number in the standard format.
+    Number(String),
+}
+
+impl Value<'_> {
+    fn as_str(&self) -> &str {
+        match self {
+            Value::Text(text) => text,
+            Value::Number(number) => number,
+        }
+    }
+}
+
+impl ExportOptions {
+    /// The names of the columns in the header and JSON Lines keys.
+    fn column_names(&self, columns: &[Column]) -> Vec<String> {
+        match self.header {
+            HeaderStyle::Technical => columns.iter().map(|c| c.name.clone()).collect(),
+            HeaderStyle::Title => columns
+                .iter()
+                .map(|column| {
+                    let title = column.title.trim();
+                    let shared = columns
+                        .iter()
+                        .filter(|other| other.title.trim() == title)
+                        .count()
+                        > 1;
+                    match title {
+                        "" => column.name.clone(),
+                        title if shared => format!("{title} ({})", column.name),
+                        title => title.to_string(),
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    fn value<'t>(&self, column: &Column, text: &'t str) -> Value<'t> {
+        match self.numbers {
+            Some(profile) if column.data_type.is_numeric() && !text.trim().is_empty() => {
+                match profile.parse_decimal(text) {
+                    Ok(number) => Value::Number(number.to_string()),
+                    Err(_) => Value::Text(text),
+                }
+            }
+            _ => Value::Text(text),
+        }
+    }
+}
+
+/// Write a CSV record, quoting fields as needed and ending it with CRLF.
+fn write_csv_record<W: Write, S: AsRef<str>>(
+    writer: &mut W,
+    fields: &[S],
+    delimiter: char,
+) -> io::Result<()> {
+    let mut line = String::new();
+    for (i, field) in fields.iter().enumerate() {
+        if i > 0 {
+            line.push(delimiter);
+        }
+        let field = field.as_ref();
+        if field.contains([delimiter, '"', '\r', '\n']) {
+            line.push('"');
+            line.push_str(&field.replace('"', "\"\""));
+            line.push('"');
+        } else {
+            line.push_str(field);
+        }
+    }
+    line.push_str("\r\n");
+    writer.write_all(line.as_bytes())
+}
+
+/// Write a tab separated record. Excel only treats quotes specially at the start of a field, so
+/// fields are quoted only if they start with one or contain a tab or line break.
+fn write_tsv_record<W: Write, S: AsRef<str>>(writer: &mut W, fields: &[S]) -> io::Result<()> {
+    let mut line = String::new();
+    for (i, field) in fields.iter().enumerate() {
+        if i > 0 {
+            line.push('\t');
+        }
+        let field = field.as_ref();
+        if field.starts_with('"') || field.contains(['\t', '\r', '\n']) {
+            line.push('"');
+            line.push_str(&field.replace('"', "\"\""));
+            line.push('"');
+        } else {
+            line.push_str(field);
+        }
+    }
+    line.push_str("\r\n");
+    writer.write_all(line.as_bytes())
+}
+
+/// The text of a cell for Excel. Excel would otherwise drop leading zeros from document numbers,
+/// read dashed or slashed numbers as dates and evaluate anything that looks like a formula, so
+/// such values are written as text formulas.
+fn excel_field(column: &Column, value: &Value<'_>) -> String {
+    match value {
+        Value::Number(number) => number.clone(),
+        Value::Text(text) => excel_text(text, column.data_type.is_numeric()),
+    }
+}
+
+/// Make text safe to paste into Excel as [`excel_field`] does, given whether it is from a
+/// numeric column.
+fn excel_text(text: &str, numeric: bool) -> String {
+    let formula =
+        text.starts_with(['=', '+', '@']) || (text.starts_with('-') && text.len() > 1 && !numeric);
+    let leading_zero =
+        text.len() > 1 && text.starts_with('0') && text.chars().all(|c| c.is_ascii_digit());
+    let number_like = !numeric
+        && text.contains(['-', '/'])
+        && text.contains(|c: char| c.is_ascii_digit())
+        && text
+            .chars()
+            .all(|c| c.is_ascii_digit() || c == '-' || c == '/');
+    if formula || leading_zero || number_like {
+        format!("=\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+/// Append a string to JSON output, quoted and escaped.
+fn push_json_string(out: &mut String, text: &str) {
+    out.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(data_type: DataType) -> Column {
+        titled("FIELD", "Field", data_type)
+    }
+
+    fn titled(name: &str, title: &str, data_type: DataType) -> Column {
+        Column {
+            name: name.to_string(),
+            title: title.to_string(),
+            data_type,
+            key: false,
+            checkbox: false,
+        }
+    }
+
+    fn export(table: &Table, format: ExportFormat, options: &ExportOptions) -> String {
+        String::from_utf8(table.export(vec![], format, options).unwrap()).unwrap()
+    }
+
+    fn csv(fields: &[&str], delimiter: char) -> String {
+        let mut out = vec![];
+        write_csv_record(&mut out, fields, delimiter).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    fn tsv(fields: &[&str]) -> String {
+        let mut out = vec![];
+        write_tsv_record(&mut out, fields).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    fn excel(data_type: DataType, text: &str) -> String {
+        excel_field(&column(data_type), &Value::Text(text))
+    }
+
+    fn json(text: &str) -> String {
+        let mut out = String::new();
+        push_json_string(&mut out, text);
+        out
+    }
+
+    #[test]
+    fn csv_quotes_only_when_needed() {
+        assert_eq!(csv(&["a", "b c", ""], ','), "a,b c,\r\n");
+        assert_eq!(csv(&["a,b", "x"], ','), "\"a,b\",x\r\n");
+        assert_eq!(csv(&["a,b", "c;d"], ';'), "a,b;\"c;d\"\r\n");
+        assert_eq!(csv(&["say \"hi\""], ','), "\"say \"\"hi\"\"\"\r\n");
+        assert_eq!(
+            csv(&["two\nlines", "cr\r"], ','),
+            "\"two\nlines\",\"cr\r\"\r\n"
+        );
+    }
+
+    #[test]
+    fn tsv_quotes_leading_quotes_tabs_and_line_breaks() {
+        assert_eq!(tsv(&["a,b", "say \"hi\""]), "a,b\tsay \"hi\"\r\n");
+        assert_eq!(tsv(&["\"quoted\""]), "\"\"\"quoted\"\"\"\r\n");
+        assert_eq!(tsv(&["a\tb", "c\nd"]), "\"a\tb\"\t\"c\nd\"\r\n");
+    }
+
+    #[test]
+    fn excel_keeps_text_as_text() {
+        assert_eq!(excel(DataType::Numc, "0000123"), "=\"0000123\"");
+        assert_eq!(excel(DataType::Char, "1-2"), "=\"1-2\"");
+        assert_eq!(excel(DataType::Char, "1/2"), "=\"1/2\"");
+        assert_eq!(excel(DataType::Char, "=SUM(A1)"), "=\"=SUM(A1)\"");
+        assert_eq!(excel(DataType::Char, "+49"), "=\"+49\"");
+        assert_eq!(excel(DataType::Char, "-x"), "=\"-x\"");
+        assert_eq!(excel(DataType::Char, "=\"a\""), "=\"=\"\"a\"\"\"");
+    }
+
+    #[test]
+    fn excel_leaves_plain_values_alone() {
+        assert_eq!(excel(DataType::Char, "Bolt"), "Bolt");
+        assert_eq!(excel(DataType::Numc, "0"), "0");
+        assert_eq!(excel(DataType::Packed, "-12"), "-12");
+        assert_eq!(excel(DataType::Char, "-"), "-");
+        assert_eq!(
+            excel_field(
+                &column(DataType::Packed),
+                &Value::Number("-1234.5".to_string())
+            ),
+            "-1234.5"
+        );
+    }
+
+    #[test]
+    fn json_escapes_quotes_and_control_characters() {
+        assert_eq!(json("plain"), "\"plain\"");
+        assert_eq!(json("a \"b\" \\ c"), "\"a \\\"b\\\" \\\\ c\"");
+        assert_eq!(json("1\n2\r3\t4"), "\"1\\n2\\r3\\t4\"");
+        assert_eq!(json("\u{1}\u{1f}"), "\"\\u0001\\u001f\"");
+        assert_eq!(json("äö €"), "\"äö €\"");
+    }
+
+    #[test]
+    fn excel_header_keeps_titles_as_text() {
+        let table = Table {
+            columns: vec![
+                titled("BUDAT", "=Date", DataType::Date),
+                titled("WRBTR", "-Amount", DataType::Packed),
+            ],
+            rows: vec![],
+        };
+        let options = ExportOptions::new().header(HeaderStyle::Title);
+        assert_eq!(
+            export(&table, ExportFormat::ExcelTsv, &options),
+            "=\"=Date\"\t=\"-Amount\"\r\n"
+        );
+    }
+
+    #[test]
+    fn json_keys_are_distinct_for_shared_titles() {
+        let table = Table {
+            columns: vec![
+                titled("WRBTR", " Amount ", DataType::Packed),
+                titled("DMBTR", "Amount", DataType::Packed),
+                titled("WAERS", "", DataType::Char),
+            ],
+            rows: vec![Row {
+                cells: vec!["1".to_string(), "2".to_string(), "EUR".to_string()],
+                selected: false,
+                total_level: 0,
+            }],
+        };
+        let options = ExportOptions::new().header(HeaderStyle::Title);
+        assert_eq!(
+            export(&table, ExportFormat::JsonLines, &options),
+            "{\"Amount (WRBTR)\":\"1\",\"Amount (DMBTR)\":\"2\",\"WAERS\":\"EUR\"}\n"
+        );
+        assert_eq!(
+            export(&table, ExportFormat::Csv, &options).lines().next(),
+            Some("Amount (WRBTR),Amount (DMBTR),WAERS")
+        );
+    }
+}
@@ -0,0 +1,555 @@
+use crate::Error;
+use crate::types::*;
+use crate::value::*;
+
+/// What to do with fields that cannot be changed when filling a form.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ReadOnlyFields {
+    /// Leave them as they are and report them as skipped.
+    #[default]
+    Skip,
+    /// Report them as failed, so nothing is set.
+    Reject,
+}
+
+/// Options for filling a form with [`GuiSession::fill_with`].
+#[derive(Clone, Debug)]
+pub struct FillOptions {
+    read_only: ReadOnlyFields,
+    validate: bool,
+}
+
+impl Default for FillOptions {
+    fn default() -> Self {
+        FillOptions {
+            read_only: ReadOnlyFields::Skip,
+            validate: true,
+        }
+    }
+}
+
+impl FillOptions {
+    /// Create the default options: skip fields that cannot be changed and validate text against
+    /// the fields it is written to.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set what to do with fields that cannot be changed.
+    pub fn read_only(mut self, read_only: ReadOnlyFields) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    /// Set whether text is checked against the length and type of its field before anything is
+    /// set. See [`GuiComponent::check_value`].
+    pub fn validate(mut self, validate: bool) -> Self {
+        self.validate = validate;
+        self
+    }
+}
+
+/// What happened to each field when filling a form.
+#[derive(Debug, Default)]
+pub struct FillReport {
+    /// The IDs of the fields that were set, in order.
+    pub set: Vec<String>,
+    /// The IDs of the fields that were left alone because they cannot be changed or are output
+    /// only.
+    pub skipped: Vec<String>,
+    /// The IDs of the fields that could not be set, with the reason.
+    pub failed: Vec<(String, Error)>,
+}
+
+impl FillReport {
+    /// Whether every field was set or skipped.
+    pub fn is_complete(&self) -> bool {
+        self.failed.is_empty()
+    }
+
+    /// Turn the first failure into an error.
+    pub fn into_result(mut self) -> crate::Result<Self, Error> {
+        if self.failed.is_empty() {
+            Ok(self)
+        } else {
+            Err(self.failed.remove(0).1)
+        }
+    }
+}
+
+impl GuiSession {
+    /// Fill a form from pairs of component ID and value, with the default [`FillOptions`].
+    /// See [`GuiSession::fill_with`], including for the order fields are set in.
+    pub fn fill<I, K, V>(&self, values: I) -> FillReport
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: AsRef<str>,
+        V: Into<SapValue>,
+    {
+        self.fill_with(values, &FillOptions::default())
+    }
+
+    /// Fill a form from pairs of component ID and value, setting them in the order they are
+    /// given. A `HashMap` gives them in no particular order, so use a `Vec` of pairs or an
+    /// ordered map such as `BTreeMap` when one field must be set before another, e.g. because
+    /// setting it changes what the others accept.
+    ///
+    /// Every field is found and checked first. If any is missing, rejected or fails validation,
+    /// nothing is set and the report lists the failures. Otherwise each value is written, and
+    /// any that fail are reported while the rest are still written.
+    pub fn fill_with<I, K, V>(&self, values: I, options: &FillOptions) -> FillReport
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: AsRef<str>,
+        V: Into<SapValue>,
+    {
+        let mut report = FillReport::default();
+        let mut to_set = vec![];
+        for (id, value) in values {
+            let id = id.as_ref().to_string();
+            let value = value.into();
+            match self.check_field(&id, &value, options) {
+                Ok(true) => to_set.push((id, value)),
+                Ok(false) => report.skipped.push(id),
+                Err(e) => report.failed.push((id, e)),
+            }
+        }
+        if !report.failed.is_empty() {
+            tracing::debug!(
+                "Not filling form, {} fields failed checks.",
+                report.failed.len()
+            );
+            return report;
+        }
+
+        for (id, value) in to_set {
+            match self.set_value(&id, value) {
+                Ok(()) => report.set.push(id),
+                Err(e) => report.failed.push((id, e)),
+            }
+        }
+        report
+    }
+
+    /// Check whether a field should be set, returning `false` if it should be skipped.
+    fn check_field(
+        &self,
+        id: &str,
+        value: &SapValue,
+        options: &FillOptions,
+    ) -> crate::Result<bool, Error> {
+        let component = self.find_by_id(id.to_string())?;
+        match component.check_value(value) {
+            Ok(()) => Ok(true),
+            Err(Error::Field { problem, .. })
+                if matches!(problem, FieldProblem::ReadOnly | FieldProblem::OutputOnly) =>
+            {
+                match options.read_only {
+                    ReadOnlyFields::Skip => Ok(false),
+                    ReadOnlyFields::Reject => Err(Error::Field {
+                        id: id.to_string(),
+                        problem,
+                    }),
+                }
+            }
+            Err(Error::Field { .. }) if !options.validate => Ok(true),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Fill a form from the fields of a struct, in declared order, with the default
+    /// [`FillOptions`]. See [`GuiSession::fill_struct_with`].
+    #[cfg(feature = "serde")]
+    pub fn fill_struct<T: serde::Serialize>(&self, value: &T) -> crate::Result<FillReport, Error> {
+        self.fill_struct_with(value, &FillOptions::default())
+    }
+
+    /// Fill a form from the fields of a struct, in declared order, as [`GuiSession::fill_with`]
+    /// does. Each field is named after the ID of its component with `#[serde(rename = "...")]`.
+    ///
+    /// Strings and numbers are written as text and booleans as flags. Fields that are `None` are
+    /// left out, so optional fields are only set when they have a value. Maps from ID to value
+    /// can be used as well, and are set in the order the map gives them.
+    ///
+    /// ```no_run
+    /// # use sap_scripting::*;
+    /// # fn example(session: &GuiSession) -> Result<(), Error> {
+    /// #[derive(serde::Serialize)]
+    /// struct Header {
+    ///     #[serde(rename = "wnd[0]/usr/ctxtBKPF-BUKRS")]
+    ///     company_code: String,
+    ///     #[serde(rename = "wnd[0]/usr/txtBKPF-XBLNR")]
+    ///     reference: Option<String>,
+    /// }
+    ///
+    /// let header = Header {
+    ///     company_code: "1000".to_string(),
+    ///     reference: None,
+    /// };
+    /// session.fill_struct(&header)?.into_result()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "serde")]
+    pub fn fill_struct_with<T: serde::Serialize>(
+        &self,
+        value: &T,
+        options: &FillOptions,
+    ) -> crate::Result<FillReport, Error> {
+        let fields = value
+            .serialize(ser::FieldsSerializer)
+            .map_err(|e| Error::Serialize { message: e.0 })?;
+        Ok(self.fill_with(fields, options))
+    }
+}
+
+/// Turning a struct or map into pairs of component ID and value.
+#[cfg(feature = "serde")]
+mod ser {
+    use std::fmt;
+
+    use serde::ser::{self, Impossible, Serialize};
+
+    use crate::value::SapValue;
+
+    /// An error serializing form values.
+    #[derive(Debug)]
+    pub(super) struct SerError(pub(super) String);
+
+    impl fmt::Display for SerError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{}", self.0)
+        }
+    }
+
+    impl std::error::Error for SerError {}
+
+    impl ser::Error for SerError {
+        fn custom<T: fmt::Display>(msg: T) -> Self {
+            SerError(msg.to_string())
+        }
+    }
+
+    fn unsupported<T>(what: &str) -> Result<T, SerError> {
+        Err(SerError(format!("{what} cannot be written to a form")))
+    }
+
+    type Fields = Vec<(String, SapValue)>;
+
+    /// Serializes a struct or map into its fields.
+    pub(super) struct FieldsSerializer;
+
+    /// Collects the fields of a struct or map.
+    pub(super) struct FieldsCollector {
+        fields: Fields,
+        key: Option<String>,
+    }
+
+    impl FieldsCollector {
+        fn push<T: ?Sized + Serialize>(&mut self, id: String, value: &T) -> Result<(), SerError> {
+            if let Some(value) = value.serialize(ValueSerializer)? {
+                self.fields.push((id, value));
+            }
+            Ok(())
+        }
+    }
+
+    impl ser::SerializeStruct for FieldsCollector {
+        type Ok = Fields;
+        type Error = SerError;
+
+        fn serialize_field<T: ?Sized + Serialize>(
+            &mut self,
+            key: &'static str,
+            value: &T,
+        ) -> Result<(), SerError> {
+            self.push(key.to_string(), value)
+        }
+
+        fn end(self) -> Result<Fields, SerError> {
+            Ok(self.fields)
+        }
+    }
+
+    impl ser::SerializeMap for FieldsCollector {
+        type Ok = Fields;
+        type Error = SerError;
+
+        fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), SerError> {
+            match key.serialize(ValueSerializer)? {
+                Some(SapValue::Text(id)) => {
+                    self.key = Some(id);
+                    Ok(())
+                }
+                _ => unsupported("a map key that is not a component ID"),
+            }
+        }
+
+        fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), SerError> {
+            let id = self.key.take().expect("key is serialized before its value");
+            self.push(id, value)
+        }
+
+        fn end(self) -> Result<Fields, SerError> {
+            Ok(self.fields)
+        }
+    }
+
+    macro_rules! unsupported_methods {
+        ($($method: ident($($arg: ty),*) -> $ret: ty, $what: literal;)*) => {
+            $(
+                fn $method(self, $(_: $arg),*) -> Result<$ret, SerError> {
+                    unsupported($what)
+                }
+            )*
+        };
+    }
+
+    impl ser::Serializer for FieldsSerializer {
+        type Ok = Fields;
+        type Error = SerError;
+        type SerializeSeq = Impossible<Fields, SerError>;
+        type SerializeTuple = Impossible<Fields, SerError>;
+        type SerializeTupleStruct = Impossible<Fields, SerError>;
+        type SerializeTupleVariant = Impossible<Fields, SerError>;
+        type SerializeMap = FieldsCollector;
+        type SerializeStruct = FieldsCollector;
+        type SerializeStructVariant = Impossible<Fields, SerError>;
+
+        fn serialize_struct(
+            self,
+            _name: &'static str,
+            len: usize,
+        ) -> Result<FieldsCollector, SerError> {
+            Ok(FieldsCollector {
+                fields: Vec::with_capacity(len),
+                key: None,
+            })
+        }
+
+        fn serialize_map(self, len: Option<usize>) -> Result<FieldsCollector, SerError> {
+            Ok(FieldsCollector {
+                fields: Vec::with_capacity(len.unwrap_or_default()),
+                key: None,
+            })
+        }
+
+        fn serialize_newtype_struct<T: ?Sized + Serialize>(
+            self,
+            _name: &'static str,
+            value: &T,
+        ) -> Result<Fields, SerError> {
+            value.serialize(self)
+        }
+
+        fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<Fields, SerError> {
+            value.serialize(self)
+        }
+
+        fn serialize_newtype_variant<T: ?Sized + Serialize>(
+            self,
+            _name: &'static str,
+            _index: u32,
+            _variant: &'static str,
+            _value: &T,
+        ) -> Result<Fields, SerError> {
+            unsupported("an enum")
+        }
+
+        fn serialize_unit_variant(
+            self,
+            _name: &'static str,
+            _index: u32,
+            _variant: &'static str,
+        ) -> Result<Fields, SerError> {
+            unsupported("an enum")
+        }
+
+        fn serialize_tuple_struct(
+            self,
+            _name: &'static str,
+            _len: usize,
+        ) -> Result<Self::SerializeTupleStruct, SerError> {
+            unsupported("a tuple struct")
+        }
+
+        fn serialize_tuple_variant(
+            self,
+            _name: &'static str,
+            _index: u32,
+            _variant: &'static str,
+            _len: usize,
+        ) -> Result<Self::SerializeTupleVariant, SerError> {
+            unsupported("an enum")
+        }
+
+        fn serialize_struct_variant(
+            self,
+            _name: &'static str,
+            _index: u32,
+            _variant: &'static str,
+            _len: usize,
+        ) -> Result<Self::SerializeStructVariant, SerError> {
+            unsupported("an enum")
+        }
+
+        fn serialize_unit_struct(self, _name: &'static str) -> Result<Fields, SerError> {
+            Ok(vec![])
+        }
+
+        unsupported_methods! {
+            serialize_bool(bool) -> Fields, "a single value";
+            serialize_i8(i8) -> Fields, "a single value";
+            serialize_i16(i16) -> Fields, "a single value";
+            serialize_i32(i32) -> Fields, "a single value";
+            serialize_i64(i64) -> Fields, "a single value";
+            serialize_u8(u8) -> Fields, "a single value";
+            serialize_u16(u16) -> Fields, "a single value";
+            serialize_u32(u32) -> Fields, "a single value";
+            serialize_u64(u64) -> Fields, "a single value";
+            serialize_f32(f32) -> Fields, "a single value";
+            serialize_f64(f64) -> Fields, "a single value";
+            serialize_char(char) -> Fields, "a single value";
+            serialize_str(&str) -> Fields, "a single value";
+            serialize_bytes(&[u8]) -> Fields, "a single value";
+            serialize_none() -> Fields, "a single value";
+            serialize_unit() -> Fields, "a single value";
+            serialize_seq(Option<usize>) -> Self::SerializeSeq, "a sequence";
+            serialize_tuple(usize) -> Self::SerializeTuple, "a tuple";
+        }
+    }
+
+    /// Serializes a single field value. Values that should be left out become `None`.
+    struct ValueSerializer;
+
+    type Value = Option<SapValue>;
+
+    macro_rules! text_methods {
+        ($($method: ident($ty: ty);)*) => {
+            $(
+                fn $method(self, value: $ty) -> Result<Value, SerError> {
+                    Ok(Some(SapValue::Text(value.to_string())))
+                }
+            )*
+        };
+    }
+
+    impl ser::Serializer for ValueSerializer {
+        type Ok = Value;
+        type Error = SerError;
+        type SerializeSeq = Impossible<Value, SerError>;
+        type SerializeTuple = Impossible<Value, SerError>;
+        type SerializeTupleStruct = Impossible<Value, SerError>;
+        type SerializeTupleVariant = Impossible<Value, SerError>;
+        type SerializeMap = Impossible<Value, SerError>;
+        type SerializeStruct = Impossible<Value, SerError>;
+        type SerializeStructVariant = Impossible<Value, SerError>;
+
+        fn serialize_bool(self, value: bool) -> Result<Value, SerError> {
+            Ok(Some(SapValue::Bool(value)))
+        }
+
+        text_methods! {
+            serialize_i8(i8);
+            serialize_i16(i16);
+            serialize_i32(i32);
+            serialize_i64(i64);
+            serialize_i128(i128);
+            serialize_u8(u8);
+            serialize_u16(u16);
+            serialize_u32(u32);
+            serialize_u64(u64);
+            serialize_u128(u128);
+            serialize_f32(f32);
+            serialize_f64(f64);
+            serialize_char(char);
+            serialize_str(&str);
+        }
+
+        fn serialize_none(self) -> Result<Value, SerError> {
+            Ok(None)
+        }
+
+        fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<Value, SerError> {
+            value.serialize(self)
+        }
+
+        fn serialize_unit(self) -> Result<Value, SerError> {
+            Ok(None)
+        }
+
+        fn serialize_unit_struct(self, _name: &'static str) -> Result<Value, SerError> {
+            Ok(None)
+        }
+
+        fn serialize_unit_variant(
+            self,
+            _name: &'static str,
+            _index: u32,
+            variant: &'static str,
+        ) -> Result<Value, SerError> {
+            Ok(Some(SapValue::Text(variant.to_string())))
+        }
+
+        fn serialize_newtype_struct<T: ?Sized + Serialize>(
+            self,
+            _name: &'static str,
+            value: &T,
+        ) -> Result<Value, SerError> {
+            value.serialize(self)
+        }
+
+        fn serialize_newtype_variant<T: ?Sized + Serialize>(
+            self,
+            _name: &'static str,
+            _index: u32,
+            _variant: &'static str,
+            _value: &T,
+        ) -> Result<Value, SerError> {
+            unsupported("an enum with data")
+        }
+
+        fn serialize_tuple_struct(
+            self,
+            _name: &'static str,
+            _len: usize,
+        ) -> Result<Self::SerializeTupleStruct, SerError> {
+            unsupported("a tuple struct")
+        }
+
+        fn serialize_tuple_variant(
+            self,
+            _name: &'static str,
+            _index: u32,
+            _variant: &'static str,
+            _len: usize,
+        ) -> Result<Self::SerializeTupleVariant, SerError> {
+            unsupported("an enum with data")
+        }
+
+        fn serialize_struct(
+            self,
+            _name: &'static str,
+            _len: usize,
+        ) -> Result<Self::SerializeStruct, SerError> {
+            unsupported("a nested struct")
+        }
+
+        fn serialize_struct_variant(
+            self,
+            _name: &'static str,
+            _index: u32,
+            _variant: &'static str,
+            _len: usize,
+        ) -> Result<Self::SerializeStructVariant, SerError> {
+            unsupported("an enum with data")
+        }
+
+        unsupported_methods! {
+            serialize_bytes(&[u8]) -> Value, "bytes";
+            serialize_seq(Option<usize>) -> Self::SerializeSeq, "a sequence";
+            serialize_tuple(usize) -> Self::SerializeTuple, "a tuple";
+            serialize_map(Option<usize>) -> Self::SerializeMap, "a nested map";
+        }
+    }
+}
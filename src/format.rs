@@ -0,0 +1,547 @@
+use std::fmt;
+use std::str::FromStr;
+
+pub use chrono::{NaiveDate, NaiveTime};
+pub use rust_decimal::Decimal;
+
+use chrono::Timelike;
+use windows::Win32::Foundation::E_FAIL;
+
+use crate::types::*;
+
+/// How numbers are written, as chosen under "Decimal Notation" in the user's defaults (`SU3`).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum DecimalNotation {
+    /// `1.234.567,89` (blank in `SU3`).
+    #[default]
+    Comma,
+    /// `1,234,567.89` (`X` in `SU3`).
+    Point,
+    /// `1 234 567,89` (`Y` in `SU3`).
+    SpaceComma,
+}
+
+impl DecimalNotation {
+    /// Interpret the code of a decimal notation, as stored in `USR01-DCPFM`.
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code.trim() {
+            "" => Some(DecimalNotation::Comma),
+            "X" => Some(DecimalNotation::Point),
+            "Y" => Some(DecimalNotation::SpaceComma),
+            _ => None,
+        }
+    }
+
+    /// The code of this decimal notation, as stored in `USR01-DCPFM`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            DecimalNotation::Comma => " ",
+            DecimalNotation::Point => "X",
+            DecimalNotation::SpaceComma => "Y",
+        }
+    }
+
+    /// The character separating whole numbers from fractions.
+    pub fn decimal_separator(&self) -> char {
+        match self {
+            DecimalNotation::Point => '.',
+            DecimalNotation::Comma | DecimalNotation::SpaceComma => ',',
+        }
+    }
+
+    /// The character separating groups of thousands.
+    pub fn grouping_separator(&self) -> char {
+        match self {
+            DecimalNotation::Comma => '.',
+            DecimalNotation::Point => ',',
+            DecimalNotation::SpaceComma => ' ',
+        }
+    }
+}
+
+/// How dates are written, as chosen under "Date Format" in the user's defaults (`SU3`).
+/// The Japanese and Islamic calendars are not supported.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum DateFormat {
+    /// `DD.MM.YYYY` (`1` in `SU3`).
+    #[default]
+    DayMonthYearDot,
+    /// `MM/DD/YYYY` (`2` in `SU3`).
+    MonthDayYearSlash,
+    /// `MM-DD-YYYY` (`3` in `SU3`).
+    MonthDayYearDash,
+    /// `YYYY.MM.DD` (`4` in `SU3`).
+    YearMonthDayDot,
+    /// `YYYY/MM/DD` (`5` in `SU3`).
+    YearMonthDaySlash,
+    /// `YYYY-MM-DD` (`6` in `SU3`).
+    YearMonthDayDash,
+}
+
+impl DateFormat {
+    /// Interpret the code of a date format, as stored in `USR01-DATFM`.
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code.trim() {
+            "1" => Some(DateFormat::DayMonthYearDot),
+            "2" => Some(DateFormat::MonthDayYearSlash),
+            "3" => Some(DateFormat::MonthDayYearDash),
+            "4" => Some(DateFormat::YearMonthDayDot),
+            "5" => Some(DateFormat::YearMonthDaySlash),
+            "6" => Some(DateFormat::YearMonthDayDash),
+            _ => None,
+        }
+    }
+
+    /// The code of this date format, as stored in `USR01-DATFM`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            DateFormat::DayMonthYearDot => "1",
+            DateFormat::MonthDayYearSlash => "2",
+            DateFormat::MonthDayYearDash => "3",
+            DateFormat::YearMonthDayDot => "4",
+            DateFormat::YearMonthDaySlash => "5",
+            DateFormat::YearMonthDayDash => "6",
+        }
+    }
+
+    /// The character separating the parts of a date.
+    pub fn separator(&self) -> char {
+        match self {
+            DateFormat::DayMonthYearDot | DateFormat::YearMonthDayDot => '.',
+            DateFormat::MonthDayYearSlash | DateFormat::YearMonthDaySlash => '/',
+            DateFormat::MonthDayYearDash | DateFormat::YearMonthDayDash => '-',
+        }
+    }
+}
+
+/// How times are written, as chosen under "Time Format" in the user's defaults (`SU3`).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum TimeFormat {
+    /// `13:05:10` (`0` in `SU3`).
+    #[default]
+    Hours24,
+    /// `01:05:10 PM`, with hours from 1 to 12 (`1` in `SU3`).
+    Hours12Upper,
+    /// `01:05:10 pm`, with hours from 1 to 12 (`2` in `SU3`).
+    Hours12Lower,
+    /// `01:05:10 PM`, with hours from 0 to 11 (`3` in `SU3`).
+    Hours11Upper,
+    /// `01:05:10 pm`, with hours from 0 to 11 (`4` in `SU3`).
+    Hours11Lower,
+}
+
+impl TimeFormat {
+    /// Interpret the code of a time format, as stored in `USR01-TIMEFM`.
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code.trim() {
+            "" | "0" => Some(TimeFormat::Hours24),
+            "1" => Some(TimeFormat::Hours12Upper),
+            "2" => Some(TimeFormat::Hours12Lower),
+            "3" => Some(TimeFormat::Hours11Upper),
+            "4" => Some(TimeFormat::Hours11Lower),
+            _ => None,
+        }
+    }
+
+    /// The code of this time format, as stored in `USR01-TIMEFM`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            TimeFormat::Hours24 => "0",
+            TimeFormat::Hours12Upper => "1",
+            TimeFormat::Hours12Lower => "2",
+            TimeFormat::Hours11Upper => "3",
+            TimeFormat::Hours11Lower => "4",
+        }
+    }
+}
+
+/// The error returned when text is not in the expected format.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FormatError {
+    /// What the text should have been, e.g. `a date like 31.12.2024`.
+    pub expected: String,
+    /// The text that could not be parsed.
+    pub text: String,
+}
+
+impl FormatError {
+    fn new<E: Into<String>>(expected: E, text: &str) -> Self {
+        FormatError {
+            expected: expected.into(),
+            text: text.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "expected {}, found \"{}\"", self.expected, self.text)
+    }
+}
+
+impl std::error::Error for FormatError {}
+
+/// The display formats of a user, used to read values from the screen and to type values in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct FormatProfile {
+    /// How numbers are written.
+    pub decimal_notation: DecimalNotation,
+    /// How dates are written.
+    pub date_format: DateFormat,
+    /// How times are written.
+    pub time_format: TimeFormat,
+}
+
+impl FormatProfile {
+    /// Work out the decimal notation of the user logged on to a session by asking SAP GUI to
+    /// convert sample numbers to the standard format.
+    ///
+    /// SAP GUI scripting does not expose the date and time formats, so these are left as
+    /// `DD.MM.YYYY` and 24 hours. Set them if the user's defaults differ.
+    ///
+    /// A number valid in every notation is converted first, so that a session that cannot convert
+    /// numbers at all fails here. After that, a sample that fails to convert is taken not to be in
+    /// the user's notation.
+    pub fn detect(session: &GuiSession) -> crate::Result<Self> {
+        let standard = |text: &str| {
+            session
+                .as_std_number_format(text.to_string())
+                .map(|s| s.trim().to_string())
+        };
+        let plain = standard("15")?;
+        if plain != "15" {
+            return Err(windows::core::Error::new(
+                E_FAIL,
+                format!("expected 15 in the standard number format, got {plain}").into(),
+            ));
+        }
+        let decimal_notation = if standard("1,5").ok().as_deref() != Some("1.5") {
+            DecimalNotation::Point
+        } else if standard("1.000,5").ok().as_deref() == Some("1000.5") {
+            DecimalNotation::Comma
+        } else {
+            DecimalNotation::SpaceComma
+        };
+        tracing::debug!("Detected decimal notation {decimal_notation:?}.");
+        Ok(FormatProfile {
+            decimal_notation,
+            ..Default::default()
+        })
+    }
+
+    /// Parse a number as displayed, e.g. `1.234,56-`. Negative numbers may have the minus sign
+    /// before or after them.
+    pub fn parse_decimal(&self, text: &str) -> Result<Decimal, FormatError> {
+        let error = || {
+            FormatError::new(
+                format!(
+                    "a number like {}",
+                    self.format_decimal(Decimal::new(-123456, 2))
+                ),
+                text,
+            )
+        };
+        let trimmed = text.trim();
+        let (negative, digits) = if let Some(rest) = trimmed.strip_suffix('-') {
+            (true, rest)
+        } else if let Some(rest) = trimmed.strip_prefix('-') {
+            (true, rest)
+        } else {
+            (false, trimmed)
+        };
+
+        let decimal = self.decimal_notation.decimal_separator();
+        let grouping = self.decimal_notation.grouping_separator();
+        let mut number = String::with_capacity(digits.len() + 1);
+        if negative {
+            number.push('-');
+        }
+        let mut fraction = false;
+        for c in digits.trim().chars() {
+            match c {
+                '0'..='9' => number.push(c),
+                c if c == decimal && !fraction => {
+                    fraction = true;
+                    number.push('.');
+                }
+                c if !fraction && (c == grouping || (grouping == ' ' && c.is_whitespace())) => (),
+                _ => return Err(error()),
+            }
+        }
+        Decimal::from_str(&number).map_err(|_| error())
+    }
+
+    /// Format a number for display, keeping its scale, e.g. `1.234,50-`.
+    pub fn format_decimal(&self, value: Decimal) -> String {
+        let negative = value.is_sign_negative() && !value.is_zero();
+        let digits = value.abs().to_string();
+        let (whole, fraction) = match digits.split_once('.') {
+            Some((whole, fraction)) => (whole, Some(fraction)),
+            None => (digits.as_str(), None),
+        };
+
+        let mut text = String::with_capacity(digits.len() + whole.len() / 3 + 1);
+        for (i, c) in whole.chars().enumerate() {
+            if i > 0 && (whole.len() - i) % 3 == 0 {
+                text.push(self.decimal_notation.grouping_separator());
+            }
+            text.push(c);
+        }
+        if let Some(fraction) = fraction {
+            text.push(self.decimal_notation.decimal_separator());
+            text.push_str(fraction);
+        }
+        if negative {
+            text.push('-');
+        }
+        text
+    }
+
+    /// Format an amount for display with a fixed number of decimal places, e.g. the decimals of
+    /// its currency, rounding half away from zero.
+    pub fn format_amount(&self, value: Decimal, decimals: u32) -> String {
+        let mut value = value.round_dp_with_strategy(
+            decimals,
+            rust_decimal::RoundingStrategy::MidpointAwayFromZero,
+        );
+        value.rescale(decimals);
+        self.format_decimal(value)
+    }
+
+    /// Parse a date as displayed, e.g. `31.12.2024`.
+    pub fn parse_date(&self, text: &str) -> Result<NaiveDate, FormatError> {
+        let error = || FormatError::new(format!("a date like {}", self.date_example()), text);
+        let parts: Vec<&str> = text.trim().split(self.date_format.separator()).collect();
+        let [a, b, c] = parts[..] else {
+            return Err(error());
+        };
+        let (year, month, day) = match self.date_format {
+            DateFormat::DayMonthYearDot => (c, b, a),
+            DateFormat::MonthDayYearSlash | DateFormat::MonthDayYearDash => (c, a, b),
+            DateFormat::YearMonthDayDot
+            | DateFormat::YearMonthDaySlash
+            | DateFormat::YearMonthDayDash => (a, b, c),
+        };
+        let number = |part: &str| part.parse::<u32>().map_err(|_| error());
+        let year = i32::try_from(number(year)?).map_err(|_| error())?;
+        NaiveDate::from_ymd_opt(year, number(month)?, number(day)?).ok_or_else(error)
+    }
+
+    /// Format a date for display, e.g. `31.12.2024`.
+    pub fn format_date(&self, date: NaiveDate) -> String {
+        let pattern = match self.date_format {
+            DateFormat::DayMonthYearDot => "%d.%m.%Y",
+            DateFormat::MonthDayYearSlash => "%m/%d/%Y",
+            DateFormat::MonthDayYearDash => "%m-%d-%Y",
+            DateFormat::YearMonthDayDot => "%Y.%m.%d",
+            DateFormat::YearMonthDaySlash => "%Y/%m/%d",
+            DateFormat::YearMonthDayDash => "%Y-%m-%d",
+        };
+        date.format(pattern).to_string()
+    }
+
+    /// Parse a time as displayed, e.g. `23:59:59` or `11:59:59 PM`. The seconds may be left out.
+    pub fn parse_time(&self, text: &str) -> Result<NaiveTime, FormatError> {
+        let error = || FormatError::new(format!("a time like {}", self.time_example()), text);
+        let trimmed = text.trim();
+        let lower = trimmed.to_ascii_lowercase();
+        let (clock, pm) = if let Some(clock) = lower.strip_suffix("pm") {
+            (clock.trim_end(), Some(true))
+        } else if let Some(clock) = lower.strip_suffix("am") {
+            (clock.trim_end(), Some(false))
+        } else {
+            (lower.as_str(), None)
+        };
+
+        let parts: Vec<&str> = clock.split(':').collect();
+        let (hour, minute, second) = match parts[..] {
+            [h, m] => (h, m, "0"),
+            [h, m, s] => (h, m, s),
+            _ => return Err(error()),
+        };
+        let number = |part: &str| part.parse::<u32>().map_err(|_| error());
+        let mut hour = number(hour)?;
+        if let Some(pm) = pm {
+            if hour > 12 {
+                return Err(error());
+            }
+            // Noon is 12:00 PM with hours from 1 to 12, and 00:00 PM with hours from 0 to 11.
+            hour = hour % 12 + if pm { 12 } else { 0 };
+        }
+        NaiveTime::from_hms_opt(hour, number(minute)?, number(second)?).ok_or_else(error)
+    }
+
+    /// Format a time for display, e.g. `23:59:59` or `11:59:59 PM`.
+    pub fn format_time(&self, time: NaiveTime) -> String {
+        let (minute, second) = (time.minute(), time.second());
+        let (pm, hour12) = time.hour12();
+        let (hour, suffix) = match self.time_format {
+            TimeFormat::Hours24 => return format!("{:02}:{minute:02}:{second:02}", time.hour()),
+            TimeFormat::Hours12Upper => (hour12, if pm { " PM" } else { " AM" }),
+            TimeFormat::Hours12Lower => (hour12, if pm { " pm" } else { " am" }),
+            TimeFormat::Hours11Upper => (time.hour() % 12, if pm { " PM" } else { " AM" }),
+            TimeFormat::Hours11Lower => (time.hour() % 12, if pm { " pm" } else { " am" }),
+        };
+        format!("{hour:02}:{minute:02}:{second:02}{suffix}")
+    }
+
+    fn date_example(&self) -> String {
+        self.format_date(NaiveDate::from_ymd_opt(2024, 12, 31).expect("valid date"))
+    }
+
+    fn time_example(&self) -> String {
+        self.format_time(NaiveTime::from_hms_opt(23, 59, 59).expect("valid time"))
+    }
+}
+
+impl GuiSession {
+    /// Work out the display formats of the user logged on to this session.
+    /// See [`FormatProfile::detect`].
+    pub fn format_profile(&self) -> crate::Result<FormatProfile> {
+        FormatProfile::detect(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(
+        decimal_notation: DecimalNotation,
+        date_format: DateFormat,
+        time_format: TimeFormat,
+    ) -> FormatProfile {
+        FormatProfile {
+            decimal_notation,
+            date_format,
+            time_format,
+        }
+    }
+
+    fn decimals(notation: DecimalNotation) -> FormatProfile {
+        profile(notation, DateFormat::default(), TimeFormat::default())
+    }
+
+    fn dates(format: DateFormat) -> FormatProfile {
+        profile(DecimalNotation::default(), format, TimeFormat::default())
+    }
+
+    fn times(format: TimeFormat) -> FormatProfile {
+        profile(DecimalNotation::default(), DateFormat::default(), format)
+    }
+
+    #[test]
+    fn decimals_round_trip() {
+        let cases = [
+            (DecimalNotation::Comma, "1.234,56-", "-1234.56"),
+            (DecimalNotation::Point, "1,234.56", "1234.56"),
+            (DecimalNotation::SpaceComma, "1 234,56", "1234.56"),
+            (DecimalNotation::Comma, "1.234.567", "1234567"),
+            (DecimalNotation::Point, "0.50", "0.50"),
+        ];
+        for (notation, text, value) in cases {
+            let profile = decimals(notation);
+            let parsed = profile.parse_decimal(text).unwrap();
+            assert_eq!(parsed, Decimal::from_str(value).unwrap(), "{text}");
+            assert_eq!(profile.format_decimal(parsed), text, "{value}");
+        }
+    }
+
+    #[test]
+    fn decimals_accept_leading_minus() {
+        let profile = decimals(DecimalNotation::Comma);
+        assert_eq!(
+            profile.parse_decimal(" -12,5 ").unwrap(),
+            Decimal::new(-125, 1)
+        );
+    }
+
+    #[test]
+    fn decimals_reject_the_other_notation() {
+        assert!(
+            decimals(DecimalNotation::Comma)
+                .parse_decimal("1,234.56")
+                .is_err()
+        );
+        assert!(
+            decimals(DecimalNotation::Point)
+                .parse_decimal("1.234,56")
+                .is_err()
+        );
+        assert!(
+            decimals(DecimalNotation::Point)
+                .parse_decimal("abc")
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn amounts_round_half_away_from_zero() {
+        let profile = decimals(DecimalNotation::Point);
+        assert_eq!(profile.format_amount(Decimal::new(12345, 3), 2), "12.35");
+        assert_eq!(profile.format_amount(Decimal::new(-12345, 3), 2), "12.35-");
+        assert_eq!(profile.format_amount(Decimal::new(5, 0), 2), "5.00");
+    }
+
+    #[test]
+    fn dates_in_every_format() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 9).unwrap();
+        let cases = [
+            (DateFormat::DayMonthYearDot, "09.03.2024"),
+            (DateFormat::MonthDayYearSlash, "03/09/2024"),
+            (DateFormat::MonthDayYearDash, "03-09-2024"),
+            (DateFormat::YearMonthDayDot, "2024.03.09"),
+            (DateFormat::YearMonthDaySlash, "2024/03/09"),
+            (DateFormat::YearMonthDayDash, "2024-03-09"),
+        ];
+        for (format, text) in cases {
+            let profile = dates(format);
+            assert_eq!(profile.format_date(date), text);
+            assert_eq!(profile.parse_date(text), Ok(date), "{text}");
+            assert_eq!(DateFormat::from_code(format.code()), Some(format));
+        }
+        assert!(
+            dates(DateFormat::DayMonthYearDot)
+                .parse_date("31.02.2024")
+                .is_err()
+        );
+        assert!(
+            dates(DateFormat::DayMonthYearDot)
+                .parse_date("2024-03-09")
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn times_at_noon_and_midnight() {
+        let midnight = NaiveTime::from_hms_opt(0, 0, 0).unwrap();
+        let noon = NaiveTime::from_hms_opt(12, 0, 0).unwrap();
+        let cases = [
+            (TimeFormat::Hours24, midnight, "00:00:00"),
+            (TimeFormat::Hours24, noon, "12:00:00"),
+            (TimeFormat::Hours12Upper, midnight, "12:00:00 AM"),
+            (TimeFormat::Hours12Upper, noon, "12:00:00 PM"),
+            (TimeFormat::Hours12Lower, midnight, "12:00:00 am"),
+            (TimeFormat::Hours12Lower, noon, "12:00:00 pm"),
+            (TimeFormat::Hours11Upper, midnight, "00:00:00 AM"),
+            (TimeFormat::Hours11Upper, noon, "00:00:00 PM"),
+            (TimeFormat::Hours11Lower, midnight, "00:00:00 am"),
+            (TimeFormat::Hours11Lower, noon, "00:00:00 pm"),
+        ];
+        for (format, time, text) in cases {
+            let profile = times(format);
+            assert_eq!(profile.format_time(time), text, "{format:?}");
+            assert_eq!(profile.parse_time(text), Ok(time), "{text}");
+        }
+    }
+
+    #[test]
+    fn times_without_seconds() {
+        let profile = times(TimeFormat::Hours12Upper);
+        assert_eq!(
+            profile.parse_time("01:30 PM"),
+            Ok(NaiveTime::from_hms_opt(13, 30, 0).unwrap())
+        );
+        assert!(profile.parse_time("13:30 PM").is_err());
+    }
+}
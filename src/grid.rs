@@ -0,0 +1,166 @@
+use com_shim::{HasIDispatch, IDispatchExt, IsA, VARIANT, VariantTypeExt};
+
+use crate::Error;
+use crate::table::*;
+use crate::types::*;
+
+impl GuiGridView {
+    /// Read every row of this grid into a [`Table`], using the current column order.
+    ///
+    /// The grid only loads the rows near the first visible row, so this scrolls through the grid a
+    /// page at a time to force every row to load, then scrolls back to where it started.
+    pub fn extract(&self) -> crate::Result<Table> {
+        let columns = self.column_info()?;
+        let mut rows = Vec::with_capacity(self.row_count()?.max(0) as usize);
+        self.read_rows(&columns, |row| {
+            rows.push(row);
+            Ok::<_, windows::core::Error>(())
+        })?;
+        Ok(Table { columns, rows })
+    }
+
+    /// The columns of this grid, in the current column order.
+    pub(crate) fn column_info(&self) -> crate::Result<Vec<Column>> {
+        let row_count = self.row_count()?;
+        let mut columns = vec![];
+        for name in self.column_names_in_order()? {
+            let checkbox = row_count > 0
+                && self
+                    .get_cell_type(0, name.clone())?
+                    .eq_ignore_ascii_case("checkbox");
+            columns.push(Column {
+                title: self.get_displayed_column_title(name.clone())?,
+                data_type: DataType::from_code(&self.get_column_data_type(name.clone())?),
+                key: self.is_column_key(name.clone())?,
+                checkbox,
+                name,
+            });
+        }
+        Ok(columns)
+    }
+
+    /// Read every row of this grid in turn, paging through it as [`GuiGridView::extract`] does.
+    pub(crate) fn read_rows<E, F>(&self, columns: &[Column], mut f: F) -> crate::Result<(), E>
+    where
+        E: From<windows::core::Error>,
+        F: FnMut(Row) -> crate::Result<(), E>,
+    {
+        let row_count = self.row_count()?;
+        let selected = parse_row_ranges(&self.selected_rows()?);
+        let page_size = self.visible_row_count()?.max(1);
+        let first_visible_row = self.first_visible_row()?;
+        tracing::debug!("Extracting {row_count} rows from grid, {page_size} at a time.");
+
+        for row in 0..row_count {
+            if row % page_size == 0 {
+                self.set_first_visible_row(row)?;
+            }
+            let mut cells = Vec::with_capacity(columns.len());
+            for column in columns {
+                cells.push(if column.checkbox {
+                    let checked = self.get_cell_check_box_checked(row, column.name.clone())?;
+                    if checked { "X" } else { "" }.to_string()
+                } else {
+                    self.get_cell_value(row, column.name.clone())?
+                });
+            }
+            f(Row {
+                cells,
+                selected: selected.contains(&row),
+                total_level: self.get_row_total_level(row)?,
+            })?;
+        }
+        if row_count > 0 {
+            self.set_first_visible_row(first_visible_row)?;
+        }
+        Ok(())
+    }
+}
+
+impl GuiGridView {
+    /// The technical names of the columns, in the order they are displayed. Use this rather than
+    /// the raw collection from [`GuiGridViewExt::column_order`].
+    pub fn column_names_in_order(&self) -> crate::Result<Vec<String>> {
+        self.read_strings("ColumnOrder")
+    }
+
+    /// Reorder the columns. Columns that are not named keep their relative order after the
+    /// named ones.
+    pub fn set_column_names_in_order<S: AsRef<str>>(&self, names: &[S]) -> crate::Result<()> {
+        let order = GuiCollection::of_strings(&self.upcast(), names)?;
+        self.set_column_order(order)
+    }
+
+    /// The selected cells, as row indexes and column names. Use this rather than the raw
+    /// collection from [`GuiGridViewExt::selected_cells`].
+    pub fn selected_cell_positions(&self) -> crate::Result<Vec<(i32, String)>> {
+        Ok(self
+            .read_strings("SelectedCells")?
+            .into_iter()
+            .filter_map(|cell| {
+                let (row, column) = cell.split_once(',')?;
+                Some((row.trim().parse().ok()?, column.trim().to_string()))
+            })
+            .collect())
+    }
+
+    /// Select cells, given as row indexes and column names, replacing the current selection.
+    pub fn select_cells<S: AsRef<str>>(&self, cells: &[(i32, S)]) -> crate::Result<()> {
+        let cells: Vec<String> = cells
+            .iter()
+            .map(|(row, column)| format!("{row},{}", column.as_ref()))
+            .collect();
+        let selection = GuiCollection::of_strings(&self.upcast(), &cells)?;
+        self.set_selected_cells(selection)
+    }
+
+    /// Select every cell in a range of rows and columns, inclusive, using the current column
+    /// order, e.g. to copy a block of cells.
+    pub fn select_range(
+        &self,
+        rows: std::ops::RangeInclusive<i32>,
+        from_column: &str,
+        to_column: &str,
+    ) -> crate::Result<(), Error> {
+        let order = self.column_names_in_order()?;
+        let position = |name: &str| {
+            order
+                .iter()
+                .position(|c| c == name)
+                .ok_or_else(|| Error::NoSuchColumn {
+                    column: name.to_string(),
+                })
+        };
+        let (from, to) = (position(from_column)?, position(to_column)?);
+        let columns = &order[from.min(to)..=from.max(to)];
+        let cells: Vec<(i32, &String)> = rows
+            .flat_map(|row| columns.iter().map(move |column| (row, column)))
+            .collect();
+        Ok(self.select_cells(&cells)?)
+    }
+
+    /// The technical names of the selected columns. Use this rather than the raw collection from
+    /// [`GuiGridViewExt::selected_columns`].
+    pub fn selected_column_names(&self) -> crate::Result<Vec<String>> {
+        self.read_strings("SelectedColumns")
+    }
+
+    /// Select columns by technical name, replacing the current selection.
+    pub fn select_column_names<S: AsRef<str>>(&self, names: &[S]) -> crate::Result<()> {
+        let selection = GuiCollection::of_strings(&self.upcast(), names)?;
+        self.set_selected_columns(selection)
+    }
+
+    /// The titles of a column. The grid shows whichever fits the width of the column. Use this
+    /// rather than the raw collection from [`GuiGridViewExt::get_column_titles`].
+    pub fn column_titles<S: Into<String>>(&self, column: S) -> crate::Result<Vec<String>> {
+        collection_strings(&self.get_idispatch().call(
+            "GetColumnTitles",
+            vec![VARIANT::variant_from(column.into())],
+        )?)
+    }
+
+    fn read_strings(&self, property: &str) -> crate::Result<Vec<String>> {
+        collection_strings(&self.get_idispatch().get(property)?)
+    }
+}
@@ -0,0 +1,290 @@
+use com_shim::{HasIDispatch, IsA};
+
+use crate::Error;
+use crate::message::StatusMessage;
+use crate::table::Table;
+use crate::types::*;
+use crate::value::*;
+
+/// How a batch of grid edits is passed to the application once they are all made.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum GridCommit {
+    /// Tell the application that the grid was modified, as leaving a changed cell does.
+    #[default]
+    TriggerModified,
+    /// Press Enter in the grid, which also runs the checks of the application.
+    PressEnter,
+    /// Leave the edits for a later action to pass on.
+    Nothing,
+}
+
+/// Options for editing a grid with [`GuiGridView::edit_rows`].
+#[derive(Clone, Debug)]
+pub struct GridEditOptions {
+    insert_rows: bool,
+    commit: GridCommit,
+    verify: bool,
+}
+
+impl Default for GridEditOptions {
+    fn default() -> Self {
+        GridEditOptions {
+            insert_rows: true,
+            commit: GridCommit::TriggerModified,
+            verify: true,
+        }
+    }
+}
+
+impl GridEditOptions {
+    /// Create the default options: insert rows as needed, trigger the modified event and read
+    /// back every edited cell.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set whether rows are appended when the edits go past the end of the grid. If not, such
+    /// edits fail.
+    pub fn insert_rows(mut self, insert_rows: bool) -> Self {
+        self.insert_rows = insert_rows;
+        self
+    }
+
+    /// Set how the edits are passed to the application.
+    pub fn commit(mut self, commit: GridCommit) -> Self {
+        self.commit = commit;
+        self
+    }
+
+    /// Set whether the edited cells are read back to find those the application rejected or
+    /// changed.
+    pub fn verify(mut self, verify: bool) -> Self {
+        self.verify = verify;
+        self
+    }
+}
+
+/// The state of a grid cell, as the application marks it after checking input.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum CellState {
+    /// The cell is not marked.
+    Normal,
+    /// The cell is marked as having an error.
+    Error,
+    /// The cell is marked with a warning.
+    Warning,
+    /// The cell is marked with information.
+    Info,
+    /// A state this crate does not know.
+    Other(String),
+}
+
+impl CellState {
+    /// Parse the state reported by `GetCellState`.
+    pub fn from_code(code: &str) -> Self {
+        match code.trim() {
+            "" | "Normal" => CellState::Normal,
+            "Error" => CellState::Error,
+            "Warning" => CellState::Warning,
+            "Info" => CellState::Info,
+            other => CellState::Other(other.to_string()),
+        }
+    }
+}
+
+/// A cell whose value or state after editing was not what was written.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CellProblem {
+    /// The row of the cell, counting from zero.
+    pub row: i32,
+    /// The technical name of the column of the cell.
+    pub column: String,
+    /// The value that was written.
+    pub wanted: SapValue,
+    /// The value the cell has now.
+    pub found: SapValue,
+    /// The state the application marked the cell with.
+    pub state: CellState,
+}
+
+/// The outcome of editing a grid with [`GuiGridView::edit_rows`].
+#[derive(Clone, Debug, Default)]
+pub struct GridEditReport {
+    /// The rows that were edited.
+    pub rows: std::ops::Range<i32>,
+    /// The number of rows that were appended.
+    pub inserted: i32,
+    /// The cells that were rejected or changed by the application. Only filled in when the
+    /// options ask for the edits to be verified.
+    pub problems: Vec<CellProblem>,
+    /// The message in the status bar after the edits were passed on, if any.
+    pub message: Option<StatusMessage>,
+}
+
+impl GridEditReport {
+    /// Whether every cell kept its value and state and no error message is shown.
+    pub fn is_clean(&self) -> bool {
+        self.problems.is_empty() && !self.message.as_ref().is_some_and(StatusMessage::is_error)
+    }
+}
+
+impl GuiGridView {
+    /// Write rows of values into an editable grid, starting at a row, with the values of each row
+    /// in the order of the named columns. A value may be `None` to leave its cell alone.
+    ///
+    /// Rows past the end of the grid are appended in one call first. The cells are then modified,
+    /// which does not go to the server, and the edits passed on with a single roundtrip as the
+    /// options ask. Finally each edited cell is read back and its state checked.
+    ///
+    /// Inserting rows and passing on the edits may rebuild the grid, so this handle may be stale
+    /// afterwards. Find the grid again to keep working with it.
+    ///
+    /// If a cell cannot be written, the error is [`Error::GridEdit`], saying how many rows were
+    /// inserted and written before it.
+    pub fn edit_rows<S, R, C, V>(
+        &self,
+        first_row: i32,
+        columns: &[S],
+        rows: R,
+        options: &GridEditOptions,
+    ) -> crate::Result<GridEditReport, Error>
+    where
+        S: AsRef<str>,
+        R: IntoIterator<Item = C>,
+        C: IntoIterator<Item = Option<V>>,
+        V: Into<SapValue>,
+    {
+        let order = self.column_names_in_order()?;
+        let columns: Vec<String> = columns.iter().map(|c| c.as_ref().to_string()).collect();
+        if let Some(missing) = columns.iter().find(|c| !order.contains(c)) {
+            return Err(Error::NoSuchColumn {
+                column: missing.clone(),
+            });
+        }
+        let edits: Vec<Vec<Option<SapValue>>> = rows
+            .into_iter()
+            .map(|row| row.into_iter().map(|v| v.map(Into::into)).collect())
+            .collect();
+        let end = first_row + edits.len() as i32;
+
+        let component: GuiComponent = self.upcast();
+        let session = component.session()?;
+        let id = self.id()?;
+        let mut grid = GuiGridView::from(self.get_idispatch().clone());
+
+        let row_count = grid.row_count()?;
+        if first_row < 0 {
+            return Err(Error::RowOutOfRange {
+                row: first_row,
+                row_count,
+            });
+        }
+        let mut inserted = 0;
+        if end > row_count {
+            if !options.insert_rows {
+                return Err(Error::RowOutOfRange {
+                    row: end - 1,
+                    row_count,
+                });
+            }
+            inserted = end - row_count;
+            tracing::debug!("Appending {inserted} rows to grid.");
+            grid.insert_rows(format!("{row_count}-{}", end - 1))?;
+            grid = find_as(&session, &id)?;
+        }
+
+        for (row, values) in (first_row..).zip(&edits) {
+            let partial = |e: Error| Error::GridEdit {
+                first_row,
+                written: (row - first_row) as usize,
+                inserted,
+                source: Box::new(e),
+            };
+            grid.show_row(row).map_err(|e| partial(e.into()))?;
+            for (column, value) in columns.iter().zip(values) {
+                if let Some(value) = value {
+                    grid.cell(row, column.clone())
+                        .set_value(value.clone())
+                        .map_err(partial)?;
+                }
+            }
+        }
+
+        match options.commit {
+            GridCommit::TriggerModified => grid.trigger_modified()?,
+            GridCommit::PressEnter => grid.press_enter()?,
+            GridCommit::Nothing => {}
+        }
+        if options.commit != GridCommit::Nothing {
+            grid = find_as(&session, &id)?;
+        }
+
+        let mut problems = vec![];
+        if options.verify {
+            for (row, values) in (first_row..).zip(edits) {
+                grid.show_row(row)?;
+                for (column, value) in columns.iter().zip(values) {
+                    let Some(wanted) = value else {
+                        continue;
+                    };
+                    let found = grid.cell(row, column.clone()).get_value()?;
+                    let state = CellState::from_code(&grid.get_cell_state(row, column.clone())?);
+                    if state != CellState::Normal || !same_value(&wanted, &found) {
+                        problems.push(CellProblem {
+                            row,
+                            column: column.clone(),
+                            wanted,
+                            found,
+                            state,
+                        });
+                    }
+                }
+            }
+        }
+
+        Ok(GridEditReport {
+            rows: first_row..end,
+            inserted,
+            problems,
+            message: session.last_message()?,
+        })
+    }
+
+    /// Write the rows of a [`Table`] into an editable grid, starting at a row, as
+    /// [`GuiGridView::edit_rows`] does. The names of the columns of the table are the technical
+    /// names of the grid columns, and check box columns take `X` or blank.
+    pub fn paste_table(
+        &self,
+        first_row: i32,
+        table: &Table,
+        options: &GridEditOptions,
+    ) -> crate::Result<GridEditReport, Error> {
+        let columns: Vec<&str> = table.columns.iter().map(|c| c.name.as_str()).collect();
+        let rows = table
+            .rows
+            .iter()
+            .map(|row| row.cells.iter().map(|cell| Some(cell.as_str())));
+        self.edit_rows(first_row, &columns, rows, options)
+    }
+
+    /// Scroll so that a row is visible, as the grid only loads the rows near those shown.
+    fn show_row(&self, row: i32) -> crate::Result<()> {
+        let first = self.first_visible_row()?;
+        if row < first || row >= first + self.visible_row_count()?.max(1) {
+            self.set_first_visible_row(row)?;
+        }
+        Ok(())
+    }
+}
+
+/// Whether a cell holds the value written to it. Text is compared without surrounding spaces.
+fn same_value(wanted: &SapValue, found: &SapValue) -> bool {
+    match found {
+        SapValue::Bool(found) => wanted.as_bool() == Some(*found),
+        SapValue::Text(found) => match wanted {
+            SapValue::Text(wanted) => wanted.trim() == found.trim(),
+            _ => false,
+        },
+        found => wanted == found,
+    }
+}
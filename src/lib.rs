@@ -11,8 +11,122 @@ pub mod types;
 
 pub use types::*;
 
+/// Typed OK-codes for the command field.
+pub mod okcode;
+
+pub use okcode::*;
+
+/// Virtual keys for frame windows.
+pub mod vkey;
+
+pub use vkey::*;
+
+/// Errors from the higher-level helpers.
+pub mod error;
+
+pub use error::*;
+
+/// Screen fingerprints and screen flows.
+pub mod screen;
+
+pub use screen::{ScreenFingerprint, ScreenFlow, ScreenPattern};
+
+/// Page objects binding several components at once.
+pub mod page;
+
+pub use page::{BindFailure, BindProblem, SapScreen};
+#[cfg(feature = "derive")]
+pub use sap_scripting_derive::SapScreen;
+
+/// Matching of displayed text.
+pub mod text_match;
+
+pub use text_match::*;
+
+/// Handling of popup windows.
+pub mod popup;
+
+pub use popup::*;
+
+/// Sessions that check for popups and status bar messages after each action.
+pub mod session;
+
+pub use session::*;
+
+/// Status bar messages.
+pub mod message;
+
+pub use message::*;
+
+/// A local catalog of message texts.
+pub mod catalog;
+
+pub use catalog::{MessageCatalog, MessageKey, MessageMatch};
+
+/// Tables extracted from grids and table controls.
+pub mod table;
+
+pub use table::*;
+
+/// Helpers for ALV grids.
+pub mod grid;
+
+/// Editing ALV grids in batches.
+pub mod grid_edit;
+
+pub use grid_edit::{CellProblem, CellState, GridCommit, GridEditOptions, GridEditReport};
+
+/// Helpers for table controls.
+pub mod table_control;
+
+pub use table_control::AppendedRows;
+
+/// Helpers for trees.
+pub mod tree;
+
+pub use tree::{SnapshotOptions, TreeColumn, TreeNode, TreeSnapshot, TreeType};
+
+/// Reading classic list reports.
+pub mod list;
+
+pub use list::ListSnapshot;
+
+/// Helpers for text editors.
+pub mod textedit;
+
+pub use textedit::{TextPosition, UnprotectedParts};
+
+/// Helpers for combo boxes.
+pub mod combo;
+
+pub use combo::ComboOption;
+
+/// Reading and writing the values of input components uniformly.
+pub mod value;
+
+pub use value::{FieldProblem, FieldValue, GridCell, SapValue};
+
+/// Filling in forms from maps and structs.
+pub mod fill;
+
+pub use fill::{FillOptions, FillReport, ReadOnlyFields};
+
+/// Parsing and formatting numbers, dates and times in the user's display format.
+pub mod format;
+
+pub use format::{DateFormat, DecimalNotation, FormatError, FormatProfile, TimeFormat};
+
+/// Exporting extracted tables to CSV, JSON Lines and Excel.
+pub mod export;
+
+pub use export::*;
+
+/// Deserializing extracted rows with serde.
+#[cfg(feature = "serde")]
+pub mod de;
+
 /// A result of a call.
-pub type Result<T> = ::windows::core::Result<T>;
+pub type Result<T, E = ::windows::core::Error> = ::std::result::Result<T, E>;
 
 /// An instance of a COM session. This should be kept whilst a connection to SAP is used.
 pub struct SAPComInstance;
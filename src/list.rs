@@ -0,0 +1,284 @@
+use com_shim::{HasIDispatch, IDispatchExt, IsA, VariantTypeExt};
+
+use crate::table::*;
+use crate::table_control::cell_text;
+use crate::types::*;
+
+/// The most pages read from one list, in case paging never reaches an unchanged page.
+const MAX_PAGES: usize = 10_000;
+
+/// The text of a classic list report, reconstructed from the labels on screen.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ListSnapshot {
+    /// The lines of the list, without trailing spaces. Lines above the list body that stay in
+    /// place while paging, such as the title, appear only once.
+    pub lines: Vec<String>,
+    /// The number of pages that were read.
+    pub pages: usize,
+}
+
+impl ListSnapshot {
+    /// The whole list as text, one line per list line.
+    pub fn text(&self) -> String {
+        self.lines.join("\n")
+    }
+
+    /// Read the lines of the list laid out in `|` separated columns as a table.
+    ///
+    /// If the first such line is followed by a rule (a line of `-`), it is taken as the header,
+    /// providing the column titles, and any repeats of it are skipped. Otherwise the columns are
+    /// named `1`, `2` and so on. Returns `None` if no line has columns.
+    pub fn table(&self) -> Option<Table> {
+        let rows: Vec<(usize, Vec<String>)> = self
+            .lines
+            .iter()
+            .enumerate()
+            .filter_map(|(i, line)| split_columns(line).map(|cells| (i, cells)))
+            .collect();
+        let (first_line, first_cells) = rows.first()?;
+
+        let header = self
+            .lines
+            .get(first_line + 1)
+            .is_some_and(|line| is_rule(line))
+            .then(|| first_cells.clone());
+        let columns = match &header {
+            Some(titles) => titles
+                .iter()
+                .map(|title| Column {
+                    name: title.clone(),
+                    title: title.clone(),
+                    data_type: DataType::Char,
+                    key: false,
+                    checkbox: false,
+                })
+                .collect(),
+            None => (1..=first_cells.len())
+                .map(|i| Column {
+                    name: i.to_string(),
+                    title: String::new(),
+                    data_type: DataType::Char,
+                    key: false,
+                    checkbox: false,
+                })
+                .collect::<Vec<_>>(),
+        };
+
+        let rows = rows
+            .into_iter()
+            .map(|(_, cells)| cells)
+            .filter(|cells| header.as_ref() != Some(cells))
+            .map(|mut cells| {
+                cells.resize(columns.len(), String::new());
+                Row {
+                    cells,
+                    selected: false,
+                    total_level: 0,
+                }
+            })
+            .collect();
+        Some(Table { columns, rows })
+    }
+}
+
+impl GuiUserArea {
+    /// The lines of the list page currently shown, laid out on a character grid as displayed.
+    pub fn page_lines(&self) -> crate::Result<Vec<String>> {
+        let mut grid: Vec<Vec<char>> = vec![];
+        for child in self.children()?.to_vec()? {
+            if !matches!(
+                child.r_type()?.as_str(),
+                "GuiLabel" | "GuiTextField" | "GuiCTextField" | "GuiCheckBox"
+            ) {
+                continue;
+            }
+            let dispatch = child.get_idispatch();
+            let left: i32 = dispatch.get("CharLeft")?.variant_into()?;
+            let top: i32 = dispatch.get("CharTop")?.variant_into()?;
+            let width: i32 = dispatch.get("CharWidth")?.variant_into()?;
+            let (Ok(left), Ok(top), Ok(width)) = (
+                usize::try_from(left),
+                usize::try_from(top),
+                usize::try_from(width),
+            ) else {
+                continue;
+            };
+
+            let text = match cell_text(&child)?.as_str() {
+                "" if child.r_type()? == "GuiCheckBox" => " ".to_string(),
+                text => text.to_string(),
+            };
+            if grid.len() <= top {
+                grid.resize(top + 1, vec![]);
+            }
+            let line = &mut grid[top];
+            let width = width.max(text.chars().count());
+            if line.len() < left + width {
+                line.resize(left + width, ' ');
+            }
+            for (i, c) in text.chars().enumerate() {
+                line[left + i] = c;
+            }
+        }
+        Ok(grid
+            .into_iter()
+            .map(|line| line.into_iter().collect::<String>().trim_end().to_string())
+            .collect())
+    }
+
+    /// Read every page of the list shown in this area, paging with `ListNavigate` from the first
+    /// page until the page no longer changes, then returning to the first page.
+    ///
+    /// Paging is a roundtrip, so this handle will be stale afterwards. Find the area again to keep
+    /// working with it.
+    pub fn list_snapshot(&self) -> crate::Result<ListSnapshot> {
+        let component: GuiComponent = self.upcast();
+        let session = component.session()?;
+        let id = self.id()?;
+
+        self.list_navigate("P--".to_string())?;
+        let mut area: GuiUserArea = find_as(&session, &id)?;
+        let first = area.page_lines()?;
+        let header = &first[..header_len(&first)];
+        let mut lines = first.clone();
+        let mut previous = first.clone();
+        let mut pages = 1;
+        while pages < MAX_PAGES {
+            area.list_navigate("P+".to_string())?;
+            area = find_as(&session, &id)?;
+            let page = area.page_lines()?;
+            if page == previous {
+                break;
+            }
+            // Lines above the body that match the first page stay in place while paging.
+            let fixed = page
+                .iter()
+                .zip(header)
+                .take_while(|(line, header)| line == header)
+                .count();
+            lines.extend(page[fixed..].iter().cloned());
+            previous = page;
+            pages += 1;
+        }
+        tracing::debug!("Read {} lines from {pages} list pages.", lines.len());
+        area.list_navigate("P--".to_string())?;
+
+        Ok(ListSnapshot { lines, pages })
+    }
+}
+
+/// The number of lines above the list body on a page, that is, before the first line with columns
+/// or the first rule. A page without either has no lines known to be above the body.
+fn header_len(page: &[String]) -> usize {
+    page.iter()
+        .position(|line| is_rule(line) || split_columns(line).is_some())
+        .unwrap_or(0)
+}
+
+/// Split a line laid out in `|` separated columns into its trimmed cells.
+fn split_columns(line: &str) -> Option<Vec<String>> {
+    let line = line.trim();
+    let inner = line.strip_prefix('|')?;
+    let inner = inner.strip_suffix('|').unwrap_or(inner);
+    if is_rule(line) {
+        return None;
+    }
+    Some(inner.split('|').map(|c| c.trim().to_string()).collect())
+}
+
+/// Whether a line is a horizontal rule, such as those under list headers.
+fn is_rule(line: &str) -> bool {
+    let line = line.trim();
+    line.contains('-') && line.chars().all(|c| matches!(c, '-' | '|' | '+' | ' '))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(lines: &[&str]) -> ListSnapshot {
+        ListSnapshot {
+            lines: lines.iter().map(|line| line.to_string()).collect(),
+            pages: 1,
+        }
+    }
+
+    #[test]
+    fn recognises_rules() {
+        assert!(is_rule("----------"));
+        assert!(is_rule("|-----+----|"));
+        assert!(is_rule("  |----|----|  "));
+        assert!(!is_rule("|    |    |"));
+        assert!(!is_rule("| -1 |  2 |"));
+        assert!(!is_rule(""));
+    }
+
+    #[test]
+    fn splits_columns() {
+        assert_eq!(
+            split_columns("| A  | B |"),
+            Some(vec!["A".to_string(), "B".to_string()])
+        );
+        assert_eq!(
+            split_columns("|A|B"),
+            Some(vec!["A".to_string(), "B".to_string()])
+        );
+        assert_eq!(
+            split_columns("| A | |"),
+            Some(vec!["A".to_string(), String::new()])
+        );
+        assert_eq!(split_columns("|----|----|"), None);
+        assert_eq!(split_columns("Title"), None);
+    }
+
+    #[test]
+    fn finds_lines_above_the_body() {
+        let page: Vec<String> = ["Title", "", "-----", "| A |", "| 1 |"]
+            .iter()
+            .map(|line| line.to_string())
+            .collect();
+        assert_eq!(header_len(&page), 2);
+        assert_eq!(header_len(&page[3..]), 0);
+        assert_eq!(header_len(&["Just text".to_string()]), 0);
+    }
+
+    #[test]
+    fn reads_a_table_with_a_header() {
+        let table = snapshot(&[
+            "Title",
+            "-----------",
+            "| Name | Qty |",
+            "|------|-----|",
+            "| A    | 1   |",
+            "| B    |",
+            "-----------",
+            "| Name | Qty |",
+            "|------|-----|",
+            "| C    | 3   | x |",
+        ])
+        .table()
+        .unwrap();
+        let titles: Vec<&str> = table.columns.iter().map(|c| c.title.as_str()).collect();
+        assert_eq!(titles, ["Name", "Qty"]);
+        let rows: Vec<Vec<&str>> = table
+            .rows
+            .iter()
+            .map(|row| row.cells.iter().map(String::as_str).collect())
+            .collect();
+        assert_eq!(rows, [vec!["A", "1"], vec!["B", ""], vec!["C", "3"]]);
+    }
+
+    #[test]
+    fn numbers_columns_without_a_header() {
+        let table = snapshot(&["| A | 1 |", "| B | 2 |"]).table().unwrap();
+        let names: Vec<&str> = table.columns.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["1", "2"]);
+        assert_eq!(table.rows.len(), 2);
+        assert_eq!(table.rows[0].cells, ["A", "1"]);
+    }
+
+    #[test]
+    fn has_no_table_without_columns() {
+        assert_eq!(snapshot(&["Title", "Some text"]).table(), None);
+    }
+}
@@ -0,0 +1,261 @@
+use std::fmt;
+
+use crate::Error;
+use crate::catalog::MessageKey;
+use crate::types::*;
+
+/// The width of each message variable (`SY-MSGV1` to `SY-MSGV4`).
+const PARAMETER_WIDTH: usize = 50;
+
+/// The type of a message shown in the status bar.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MessageType {
+    /// `S`: the action completed successfully.
+    Success,
+    /// `I`: information.
+    Information,
+    /// `W`: a warning, the action can continue.
+    Warning,
+    /// `E`: an error, the input must be corrected.
+    Error,
+    /// `A`: the transaction was terminated.
+    Abort,
+    /// `X`: a short dump was raised.
+    Exit,
+}
+
+impl MessageType {
+    /// Parse the single-letter code SAP uses for a message type.
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code.trim() {
+            "S" => Some(MessageType::Success),
+            "I" => Some(MessageType::Information),
+            "W" => Some(MessageType::Warning),
+            "E" => Some(MessageType::Error),
+            "A" => Some(MessageType::Abort),
+            "X" => Some(MessageType::Exit),
+            _ => None,
+        }
+    }
+
+    /// The single-letter code SAP uses for this message type.
+    pub fn code(&self) -> char {
+        match self {
+            MessageType::Success => 'S',
+            MessageType::Information => 'I',
+            MessageType::Warning => 'W',
+            MessageType::Error => 'E',
+            MessageType::Abort => 'A',
+            MessageType::Exit => 'X',
+        }
+    }
+}
+
+impl fmt::Display for MessageType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
+
+/// A message shown in the status bar.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StatusMessage {
+    /// The type of the message.
+    pub message_type: MessageType,
+    /// The message class, e.g. `V1`.
+    pub class: String,
+    /// The message number within its class, e.g. `001`.
+    pub number: String,
+    /// The message parameters, substituted for `&1` to `&4`.
+    pub parameters: Vec<String>,
+    /// The text of the message, as displayed.
+    pub text: String,
+    /// Whether the message has a long text.
+    pub has_long_text: bool,
+    /// Whether the message was shown as a popup rather than in the status bar.
+    pub as_popup: bool,
+}
+
+impl StatusMessage {
+    /// Read the message currently shown by a status bar, if there is one.
+    pub fn read(statusbar: &GuiStatusbar) -> crate::Result<Option<Self>> {
+        let Some(message_type) = MessageType::from_code(&statusbar.message_type()?) else {
+            return Ok(None);
+        };
+        Ok(Some(StatusMessage {
+            message_type,
+            class: statusbar.message_id()?.trim().to_string(),
+            number: statusbar.message_number()?.trim().to_string(),
+            parameters: split_parameters(&statusbar.message_parameter()?),
+            text: statusbar.text()?,
+            has_long_text: statusbar.message_has_long_text()? != 0,
+            as_popup: statusbar.message_as_popup()?,
+        }))
+    }
+
+    /// Get a parameter by its placeholder number, from 1 to 4.
+    pub fn parameter(&self, n: usize) -> Option<&str> {
+        n.checked_sub(1)
+            .and_then(|i| self.parameters.get(i))
+            .map(String::as_str)
+    }
+
+    /// Whether this is an error, abort or exit message.
+    pub fn is_error(&self) -> bool {
+        matches!(
+            self.message_type,
+            MessageType::Error | MessageType::Abort | MessageType::Exit
+        )
+    }
+
+    /// Whether this is a warning.
+    pub fn is_warning(&self) -> bool {
+        self.message_type == MessageType::Warning
+    }
+
+    /// Whether this is a success message.
+    pub fn is_success(&self) -> bool {
+        self.message_type == MessageType::Success
+    }
+}
+
+impl fmt::Display for StatusMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {}/{}: {}",
+            self.message_type, self.class, self.number, self.text
+        )
+    }
+}
+
+/// Split the raw `MessageParameter` value into its parameters. SAP GUI reports them as one
+/// string, either separated by tabs or padded to the width of the message variables.
+///
+/// See `MessageParameter` of `GuiStatusbar` in the SAP GUI Scripting API reference. The padded
+/// layout follows the message variables `SY-MSGV1` to `SY-MSGV4` of structure `SYST`, which are
+/// `CHAR 50`. Trailing empty parameters are dropped.
+fn split_parameters(raw: &str) -> Vec<String> {
+    let mut parameters: Vec<String> = if raw.contains('\t') {
+        raw.split('\t').map(|p| p.trim().to_string()).collect()
+    } else {
+        let chars: Vec<char> = raw.chars().collect();
+        chars
+            .chunks(PARAMETER_WIDTH)
+            .map(|p| p.iter().collect::<String>().trim().to_string())
+            .collect()
+    };
+    while parameters.last().is_some_and(String::is_empty) {
+        parameters.pop();
+    }
+    parameters
+}
+
+impl GuiStatusbar {
+    /// Read the message currently shown, if there is one.
+    pub fn message(&self) -> crate::Result<Option<StatusMessage>> {
+        StatusMessage::read(self)
+    }
+}
+
+impl GuiSession {
+    /// Read the message currently shown in the status bar of the main window, if there is one.
+    pub fn last_message(&self) -> crate::Result<Option<StatusMessage>> {
+        let statusbar: GuiStatusbar = find_as(self, "wnd[0]/sbar")?;
+        statusbar.message()
+    }
+}
+
+/// How warning messages are treated by a [`StatusPolicy`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum WarningTreatment {
+    /// Accept warnings silently.
+    Ignore,
+    /// Accept warnings, logging them.
+    #[default]
+    Log,
+    /// Treat warnings as errors.
+    Fail,
+}
+
+/// Rules deciding which status bar messages should fail an action.
+///
+/// Error and abort messages always fail, unless their class and number are allowed.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StatusPolicy {
+    warnings: WarningTreatment,
+    allowed: Vec<MessageKey>,
+}
+
+impl StatusPolicy {
+    /// Create a policy that fails on errors and logs warnings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set how warnings are treated.
+    pub fn warnings(mut self, treatment: WarningTreatment) -> Self {
+        self.warnings = treatment;
+        self
+    }
+
+    /// Never fail because of a particular message, e.g. `allow("V1", "001")`. The class and
+    /// number are normalised as by [`MessageKey::new`], so `allow("v1", "1")` is the same.
+    pub fn allow<C: AsRef<str>, N: AsRef<str>>(mut self, class: C, number: N) -> Self {
+        self.allowed.push(MessageKey::new(class, number));
+        self
+    }
+
+    /// Check if a message is allowed.
+    pub fn is_allowed(&self, message: &StatusMessage) -> bool {
+        let key = message.key();
+        self.allowed.contains(&key)
+    }
+
+    /// Check a message against this policy, failing with [`Error::Message`] if it is not acceptable.
+    pub fn check(&self, message: &StatusMessage) -> crate::Result<(), Error> {
+        if self.is_allowed(message) {
+            return Ok(());
+        }
+        let fail = match message.message_type {
+            MessageType::Error | MessageType::Abort | MessageType::Exit => true,
+            MessageType::Warning => match self.warnings {
+                WarningTreatment::Ignore => false,
+                WarningTreatment::Log => {
+                    tracing::warn!("{message}");
+                    false
+                }
+                WarningTreatment::Fail => true,
+            },
+            MessageType::Success | MessageType::Information => false,
+        };
+        if fail {
+            Err(Error::Message(Box::new(message.clone())))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_tab_separated_parameters() {
+        assert_eq!(split_parameters("4711\tEUR"), ["4711", "EUR"]);
+        assert_eq!(split_parameters(" A \t\tC\t\t"), ["A", "", "C"]);
+        assert!(split_parameters("").is_empty());
+    }
+
+    #[test]
+    fn splits_padded_parameters() {
+        let raw = format!("{:<50}{:<50}{:<50}{:<50}", "4711", "EUR", "", "");
+        assert_eq!(split_parameters(&raw), ["4711", "EUR"]);
+        let raw = format!("{:<50}{:<50}X", "A", "");
+        assert_eq!(split_parameters(&raw), ["A", "", "X"]);
+        let long = "L".repeat(50);
+        let raw = format!("{long}B");
+        assert_eq!(split_parameters(&raw), [long.as_str(), "B"]);
+    }
+}
@@ -0,0 +1,56 @@
+use std::fmt;
+
+use crate::types::*;
+use crate::vkey::*;
+
+/// A command that can be entered into the OK-code (command) field of the main window.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum OkCode {
+    /// Start a transaction in the current session, ending the current one (`/n<tcode>`).
+    /// An empty transaction code returns to the SAP Easy Access menu.
+    SameSession(String),
+    /// Start a transaction in a new session (`/o<tcode>`).
+    /// An empty transaction code lists the open sessions.
+    NewSession(String),
+    /// Start a transaction, skipping its first screen (`/*<tcode>`).
+    SkipFirstScreen(String),
+    /// Delete the current session (`/i`).
+    EndSession,
+    /// Log off, asking for confirmation (`/nend`).
+    Logoff,
+    /// Log off without confirmation, discarding unsaved data (`/nex`).
+    LogoffImmediately,
+    /// Save the current list to a local file (`%pc`).
+    ListSave,
+    /// Switch on the ABAP debugger (`/h`).
+    Debugger,
+    /// Any other command, submitted exactly as given.
+    Custom(String),
+}
+
+impl fmt::Display for OkCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OkCode::SameSession(tcode) => write!(f, "/n{tcode}"),
+            OkCode::NewSession(tcode) => write!(f, "/o{tcode}"),
+            OkCode::SkipFirstScreen(tcode) => write!(f, "/*{tcode}"),
+            OkCode::EndSession => write!(f, "/i"),
+            OkCode::Logoff => write!(f, "/nend"),
+            OkCode::LogoffImmediately => write!(f, "/nex"),
+            OkCode::ListSave => write!(f, "%pc"),
+            OkCode::Debugger => write!(f, "/h"),
+            OkCode::Custom(code) => write!(f, "{code}"),
+        }
+    }
+}
+
+impl GuiSession {
+    /// Enter an OK-code into the command field of the main window (`wnd[0]/tbar[0]/okcd`) and submit it.
+    pub fn execute(&self, code: OkCode) -> crate::Result<()> {
+        tracing::debug!("Executing OK-code {code}.");
+        let okcd: GuiOkCodeField = find_as(self, "wnd[0]/tbar[0]/okcd")?;
+        okcd.set_text(code.to_string())?;
+        let wnd: GuiMainWindow = find_as(self, "wnd[0]")?;
+        wnd.send_vkey(VKey::Enter)
+    }
+}
@@ -0,0 +1,93 @@
+use std::fmt;
+
+use crate::Error;
+use crate::types::*;
+
+/// A group of components on a screen that are found and type-checked together.
+///
+/// With the `derive` feature enabled, this can be derived for a struct whose fields are SAP GUI
+/// components, each annotated with the ID it binds to:
+///
+/// ```ignore
+/// #[derive(SapScreen)]
+/// struct PostDocument {
+///     #[sap(id = "wnd[0]/usr/ctxtFKKKO-BLART")]
+///     doc_type: GuiCTextField,
+///     #[sap(id = "wnd[0]/usr/txtFKKKO-XBLNR")]
+///     reference: GuiTextField,
+/// }
+/// ```
+pub trait SapScreen: Sized {
+    /// Find every component of this screen in the session. If any are missing or of the wrong
+    /// type, all of the failures are reported together in [`Error::Bind`].
+    fn bind(session: &GuiSession) -> crate::Result<Self, Error>;
+}
+
+/// A component that could not be bound.
+#[derive(Debug)]
+pub struct BindFailure {
+    /// The name of the field the component was bound to.
+    pub field: &'static str,
+    /// The ID of the component.
+    pub id: String,
+    /// Why the component could not be bound.
+    pub problem: BindProblem,
+}
+
+/// The reason a component could not be bound.
+#[derive(Debug)]
+pub enum BindProblem {
+    /// No component could be found with the ID.
+    Missing(windows::core::Error),
+    /// A component was found, but it is of another type.
+    WrongType {
+        /// The type the field expects.
+        expected: &'static str,
+        /// The type of the component that was found.
+        found: String,
+    },
+}
+
+impl fmt::Display for BindFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.problem {
+            BindProblem::Missing(e) => write!(f, "{} ({}): not found: {e}", self.field, self.id),
+            BindProblem::WrongType { expected, found } => write!(
+                f,
+                "{} ({}): expected {expected}, found {found}",
+                self.field, self.id
+            ),
+        }
+    }
+}
+
+/// Find a component for a field of a [`SapScreen`], recording a failure if it cannot be bound.
+#[doc(hidden)]
+pub fn bind_field<T>(
+    session: &GuiSession,
+    field: &'static str,
+    id: &str,
+    failures: &mut Vec<BindFailure>,
+) -> Option<T>
+where
+    T: HasSAPType + From<windows::Win32::System::Com::IDispatch>,
+{
+    let problem = match session.find_by_id(id.to_string()) {
+        Ok(component) => match component.downcast() {
+            Some(bound) => return Some(bound),
+            None => BindProblem::WrongType {
+                expected: T::sap_subtype().unwrap_or_else(|| T::sap_type()),
+                found: component
+                    .kind()
+                    .unwrap_or_else(|e| format!("<unknown: {e}>")),
+            },
+        },
+        Err(e) => BindProblem::Missing(e),
+    };
+    failures.push(BindFailure {
+        field,
+        id: id.to_string(),
+        problem,
+    });
+    None
+}
@@ -0,0 +1,259 @@
+use com_shim::HasIDispatch;
+
+use crate::Error;
+use crate::screen::ScreenFingerprint;
+use crate::text_match::TextMatch;
+use crate::types::*;
+use crate::vkey::*;
+
+/// The number of popups handled in a row before giving up, in case a response does not close its popup.
+const MAX_POPUPS: usize = 10;
+
+/// A modal window that is currently open.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Popup {
+    /// The screen of the popup.
+    pub fingerprint: ScreenFingerprint,
+    /// The text of the popup. For popup dialogs this is `PopupDialogText`, otherwise the texts of its
+    /// labels and output fields, one per line.
+    pub text: String,
+}
+
+impl Popup {
+    /// Read the popup that is currently open in a session, if there is one.
+    pub fn current(session: &GuiSession) -> crate::Result<Option<Popup>> {
+        let window = session.active_window()?;
+        let Some(modal) = window.downcast::<GuiModalWindow>() else {
+            return Ok(None);
+        };
+        let text = if modal.is_popup_dialog()? {
+            modal.popup_dialog_text()?
+        } else {
+            let mut lines = vec![];
+            for component in descendants(&window)? {
+                let kind = component.r_type()?;
+                if kind == "GuiLabel" || kind == "GuiTextField" {
+                    let text = GuiVComponent::from(component.get_idispatch().clone()).text()?;
+                    if !text.trim().is_empty() {
+                        lines.push(text.trim().to_string());
+                    }
+                }
+            }
+            lines.join("\n")
+        };
+        Ok(Some(Popup {
+            fingerprint: session.fingerprint()?,
+            text,
+        }))
+    }
+
+    /// The title of the popup.
+    pub fn title(&self) -> &str {
+        &self.fingerprint.title
+    }
+
+    fn into_error(self) -> Error {
+        Error::Popup {
+            title: self.fingerprint.title,
+            text: self.text,
+        }
+    }
+}
+
+/// Something a popup can be recognised by.
+#[derive(Clone, Debug)]
+pub enum PopupMatcher {
+    /// The title of the window.
+    Title(TextMatch),
+    /// The text of the popup.
+    Text(TextMatch),
+    /// The program and screen number of the popup.
+    Screen {
+        /// The ABAP program, e.g. `SAPLSPO1`.
+        program: String,
+        /// The dynpro number.
+        screen_number: i32,
+    },
+}
+
+impl PopupMatcher {
+    /// Check if a popup matches.
+    pub fn matches(&self, popup: &Popup) -> bool {
+        match self {
+            PopupMatcher::Title(m) => m.matches(popup.title()),
+            PopupMatcher::Text(m) => m.matches(&popup.text),
+            PopupMatcher::Screen {
+                program,
+                screen_number,
+            } => {
+                program.eq_ignore_ascii_case(&popup.fingerprint.program)
+                    && *screen_number == popup.fingerprint.screen_number
+            }
+        }
+    }
+}
+
+/// What to do with a popup once it has been recognised.
+#[derive(Clone, Debug)]
+pub enum PopupResponse {
+    /// Press the button whose text or tooltip matches.
+    PressButton(TextMatch),
+    /// Send a virtual key to the popup.
+    SendVKey(VKey),
+    /// Stop with [`Error::Popup`].
+    Abort,
+}
+
+/// A rule recognising a popup and responding to it.
+#[derive(Clone, Debug)]
+pub struct PopupRule {
+    matchers: Vec<PopupMatcher>,
+    response: PopupResponse,
+}
+
+impl PopupRule {
+    /// Create a rule responding to any popup. Narrow it down with [`PopupRule::title`],
+    /// [`PopupRule::text`] and [`PopupRule::screen`].
+    pub fn new(response: PopupResponse) -> Self {
+        PopupRule {
+            matchers: vec![],
+            response,
+        }
+    }
+
+    /// Only respond to popups whose title matches.
+    pub fn title<M: Into<TextMatch>>(mut self, title: M) -> Self {
+        self.matchers.push(PopupMatcher::Title(title.into()));
+        self
+    }
+
+    /// Only respond to popups whose text matches.
+    pub fn text<M: Into<TextMatch>>(mut self, text: M) -> Self {
+        self.matchers.push(PopupMatcher::Text(text.into()));
+        self
+    }
+
+    /// Only respond to popups showing this program and screen number.
+    pub fn screen<S: Into<String>>(mut self, program: S, screen_number: i32) -> Self {
+        self.matchers.push(PopupMatcher::Screen {
+            program: program.into(),
+            screen_number,
+        });
+        self
+    }
+
+    /// Check if a popup matches every condition of this rule.
+    pub fn matches(&self, popup: &Popup) -> bool {
+        self.matchers.iter().all(|m| m.matches(popup))
+    }
+
+    /// The response of this rule.
+    pub fn response(&self) -> &PopupResponse {
+        &self.response
+    }
+}
+
+/// What to do with a popup that matches no rule.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum UnmatchedPopup {
+    /// Leave it open for the caller to deal with.
+    #[default]
+    Ignore,
+    /// Stop with [`Error::Popup`].
+    Abort,
+}
+
+/// An ordered set of rules for dealing with popups. The first matching rule is used.
+#[derive(Clone, Debug, Default)]
+pub struct PopupHandlers {
+    rules: Vec<PopupRule>,
+    unmatched: UnmatchedPopup,
+}
+
+impl PopupHandlers {
+    /// Create a registry with no rules, which ignores every popup.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a rule, after every rule already registered.
+    pub fn register(&mut self, rule: PopupRule) -> &mut Self {
+        self.rules.push(rule);
+        self
+    }
+
+    /// Set what to do with popups that match no rule.
+    pub fn set_unmatched(&mut self, unmatched: UnmatchedPopup) -> &mut Self {
+        self.unmatched = unmatched;
+        self
+    }
+
+    /// Respond to any open popups in a session, returning how many were dealt with.
+    pub fn handle(&self, session: &GuiSession) -> crate::Result<usize, Error> {
+        let mut handled = 0;
+        while let Some(popup) = Popup::current(session)? {
+            if handled == MAX_POPUPS {
+                tracing::error!(
+                    "Popup \"{}\" is still open after responding.",
+                    popup.title()
+                );
+                return Err(popup.into_error());
+            }
+            let Some(rule) = self.rules.iter().find(|r| r.matches(&popup)) else {
+                tracing::debug!("No rule for popup \"{}\".", popup.title());
+                return match self.unmatched {
+                    UnmatchedPopup::Ignore => Ok(handled),
+                    UnmatchedPopup::Abort => Err(popup.into_error()),
+                };
+            };
+            tracing::debug!(
+                "Responding to popup \"{}\" with {:?}.",
+                popup.title(),
+                rule.response
+            );
+            let window = session.active_window()?;
+            match &rule.response {
+                PopupResponse::PressButton(label) => {
+                    let mut button = None;
+                    for component in descendants(&window)? {
+                        if let Some(b) = component.downcast::<GuiButton>()
+                            && (label.matches(&b.text()?) || label.matches(&b.tooltip()?))
+                        {
+                            button = Some(b);
+                            break;
+                        }
+                    }
+                    match button {
+                        Some(button) => button.press()?,
+                        None => {
+                            tracing::error!("Popup \"{}\" has no button {label}.", popup.title());
+                            return Err(popup.into_error());
+                        }
+                    }
+                }
+                PopupResponse::SendVKey(key) => {
+                    GuiFrameWindow::from(window.get_idispatch().clone()).send_vkey(*key)?
+                }
+                PopupResponse::Abort => return Err(popup.into_error()),
+            }
+            handled += 1;
+        }
+        Ok(handled)
+    }
+}
+
+/// Collect every component below a container, depth first.
+fn descendants(container: &GuiComponent) -> crate::Result<Vec<GuiComponent>> {
+    let mut found = vec![];
+    let children = GuiContainer::from(container.get_idispatch().clone()).children()?;
+    for child in children.to_vec()? {
+        let below = if child.container_type()? {
+            descendants(&child)?
+        } else {
+            vec![]
+        };
+        found.push(child);
+        found.extend(below);
+    }
+    Ok(found)
+}
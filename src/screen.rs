@@ -0,0 +1,218 @@
+use std::fmt;
+
+use com_shim::HasIDispatch;
+
+use crate::Error;
+use crate::types::*;
+
+/// Identifies the screen a session is currently showing.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ScreenFingerprint {
+    /// The ABAP program of the screen, e.g. `SAPLFKPP`.
+    pub program: String,
+    /// The dynpro number of the screen.
+    pub screen_number: i32,
+    /// The transaction code the session is running.
+    pub transaction: String,
+    /// The ID of the active window, e.g. `/app/con[0]/ses[0]/wnd[1]`.
+    pub window_id: String,
+    /// The title of the active window.
+    pub title: String,
+}
+
+impl ScreenFingerprint {
+    /// Capture the fingerprint of the screen a session is showing.
+    pub fn capture(session: &GuiSession) -> crate::Result<Self> {
+        let info = session.info()?;
+        let window = session.active_window()?;
+        let title = GuiVComponent::from(window.get_idispatch().clone()).text()?;
+        Ok(ScreenFingerprint {
+            program: info.program()?,
+            screen_number: info.screen_number()?,
+            transaction: info.transaction()?,
+            window_id: window.id()?,
+            title,
+        })
+    }
+
+    /// The last part of the window ID, e.g. `wnd[1]`.
+    pub fn window(&self) -> &str {
+        self.window_id.rsplit('/').next().unwrap_or(&self.window_id)
+    }
+}
+
+impl fmt::Display for ScreenFingerprint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}/{:04} ({}) {} \"{}\"",
+            self.program,
+            self.screen_number,
+            self.transaction,
+            self.window(),
+            self.title
+        )
+    }
+}
+
+impl GuiSession {
+    /// Capture the fingerprint of the screen this session is showing.
+    pub fn fingerprint(&self) -> crate::Result<ScreenFingerprint> {
+        ScreenFingerprint::capture(self)
+    }
+}
+
+/// A pattern matching screen fingerprints. Parts that are not set match anything.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ScreenPattern {
+    program: Option<String>,
+    screen_number: Option<i32>,
+    transaction: Option<String>,
+    window: Option<String>,
+    title: Option<String>,
+}
+
+impl ScreenPattern {
+    /// A pattern matching any screen.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A pattern matching a program and screen number.
+    pub fn screen_of<S: Into<String>>(program: S, screen_number: i32) -> Self {
+        Self::new().program(program).screen_number(screen_number)
+    }
+
+    /// Only match screens of this program.
+    pub fn program<S: Into<String>>(mut self, program: S) -> Self {
+        self.program = Some(program.into());
+        self
+    }
+
+    /// Only match this screen number.
+    pub fn screen_number(mut self, screen_number: i32) -> Self {
+        self.screen_number = Some(screen_number);
+        self
+    }
+
+    /// Only match screens shown while running this transaction.
+    pub fn transaction<S: Into<String>>(mut self, transaction: S) -> Self {
+        self.transaction = Some(transaction.into());
+        self
+    }
+
+    /// Only match when this window is active, e.g. `wnd[1]`.
+    pub fn window<S: Into<String>>(mut self, window: S) -> Self {
+        self.window = Some(window.into());
+        self
+    }
+
+    /// Only match windows whose title contains this text.
+    pub fn title_contains<S: Into<String>>(mut self, title: S) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// Check if a fingerprint matches this pattern.
+    pub fn matches(&self, fingerprint: &ScreenFingerprint) -> bool {
+        self.program
+            .as_ref()
+            .is_none_or(|p| p.eq_ignore_ascii_case(&fingerprint.program))
+            && self
+                .screen_number
+                .is_none_or(|n| n == fingerprint.screen_number)
+            && self
+                .transaction
+                .as_ref()
+                .is_none_or(|t| t.eq_ignore_ascii_case(&fingerprint.transaction))
+            && self
+                .window
+                .as_ref()
+                .is_none_or(|w| w == fingerprint.window())
+            && self
+                .title
+                .as_ref()
+                .is_none_or(|t| fingerprint.title.contains(t.as_str()))
+    }
+}
+
+/// What a screen flow should do after a handler has run.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Step {
+    /// Capture the next screen and dispatch it to its handler.
+    Continue,
+    /// The flow is complete.
+    Done,
+}
+
+type Handler<'a> =
+    Box<dyn FnMut(&GuiSession, &ScreenFingerprint) -> crate::Result<Step, Error> + 'a>;
+
+/// A state machine that dispatches each screen a session shows to the handler registered for it.
+///
+/// Handlers are tried in the order they were registered. If a screen matches no handler, the flow
+/// stops with [`Error::UnknownScreen`] rather than acting on the wrong screen.
+pub struct ScreenFlow<'a> {
+    handlers: Vec<(ScreenPattern, Handler<'a>)>,
+    max_steps: usize,
+}
+
+impl Default for ScreenFlow<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> ScreenFlow<'a> {
+    /// Create a flow with no handlers, allowing up to 100 steps.
+    pub fn new() -> Self {
+        ScreenFlow {
+            handlers: vec![],
+            max_steps: 100,
+        }
+    }
+
+    /// Register a handler for screens matching a pattern.
+    pub fn on<F>(mut self, pattern: ScreenPattern, handler: F) -> Self
+    where
+        F: FnMut(&GuiSession, &ScreenFingerprint) -> crate::Result<Step, Error> + 'a,
+    {
+        self.handlers.push((pattern, Box::new(handler)));
+        self
+    }
+
+    /// Set the maximum number of screens handled before the flow is considered stuck.
+    pub fn max_steps(mut self, max_steps: usize) -> Self {
+        self.max_steps = max_steps;
+        self
+    }
+
+    /// Run the flow until a handler returns [`Step::Done`], returning the screen it finished on.
+    pub fn run(&mut self, session: &GuiSession) -> crate::Result<ScreenFingerprint, Error> {
+        for _ in 0..self.max_steps {
+            let fingerprint = session.fingerprint()?;
+            tracing::debug!("Screen flow reached {fingerprint}.");
+            let Some((_, handler)) = self
+                .handlers
+                .iter_mut()
+                .find(|(pattern, _)| pattern.matches(&fingerprint))
+            else {
+                let dump = session
+                    .get_object_tree(fingerprint.window_id.clone())
+                    .unwrap_or_else(|e| format!("<object tree unavailable: {e}>"));
+                tracing::error!("No handler for screen {fingerprint}:\n{dump}");
+                return Err(Error::UnknownScreen {
+                    fingerprint: Box::new(fingerprint),
+                    dump,
+                });
+            };
+            if handler(session, &fingerprint)? == Step::Done {
+                return Ok(fingerprint);
+            }
+        }
+        Err(Error::StepLimit {
+            limit: self.max_steps,
+            fingerprint: Box::new(session.fingerprint()?),
+        })
+    }
+}
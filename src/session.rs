@@ -0,0 +1,160 @@
+use std::ops::Deref;
+
+use com_shim::{HasIDispatch, IDispatchExt, VariantTypeExt};
+
+use crate::Error;
+use crate::message::StatusPolicy;
+use crate::okcode::OkCode;
+use crate::popup::*;
+use crate::types::*;
+use crate::value::SapValue;
+use crate::vkey::*;
+
+/// A session that checks for popups, and optionally the status bar, after every action performed
+/// through it.
+///
+/// Components can still be reached through the underlying [`GuiSession`], which this dereferences
+/// to, but only the actions on this type are followed by the checks. Call
+/// [`ManagedSession::after_action`] to run them after acting on a component directly.
+pub struct ManagedSession {
+    session: GuiSession,
+    popups: PopupHandlers,
+    status_policy: Option<StatusPolicy>,
+    validate_input: bool,
+}
+
+impl ManagedSession {
+    /// Manage a session, initially ignoring any popups and status bar messages.
+    pub fn new(session: GuiSession) -> Self {
+        ManagedSession {
+            session,
+            popups: PopupHandlers::new(),
+            status_policy: None,
+            validate_input: false,
+        }
+    }
+
+    /// The underlying session.
+    pub fn session(&self) -> &GuiSession {
+        &self.session
+    }
+
+    /// The popup handlers of this session.
+    pub fn popups(&mut self) -> &mut PopupHandlers {
+        &mut self.popups
+    }
+
+    /// Register a popup rule, after every rule already registered.
+    pub fn on_popup(&mut self, rule: PopupRule) -> &mut Self {
+        self.popups.register(rule);
+        self
+    }
+
+    /// Check the status bar after each action, failing with [`Error::Message`] when the policy
+    /// rejects the message shown. `None` disables the check.
+    pub fn set_status_policy(&mut self, policy: Option<StatusPolicy>) -> &mut Self {
+        self.status_policy = policy;
+        self
+    }
+
+    /// The status bar policy, if one is set.
+    pub fn status_policy(&self) -> Option<&StatusPolicy> {
+        self.status_policy.as_ref()
+    }
+
+    /// Check text before it is set with [`ManagedSession::set_text`], failing with
+    /// [`Error::Field`] instead of setting text that the field would cut off or not take. See
+    /// [`GuiComponent::check_value`].
+    pub fn set_input_validation(&mut self, validate: bool) -> &mut Self {
+        self.validate_input = validate;
+        self
+    }
+
+    /// Whether text is checked before it is set.
+    pub fn input_validation(&self) -> bool {
+        self.validate_input
+    }
+
+    /// Run the checks that follow each action.
+    pub fn after_action(&self) -> crate::Result<(), Error> {
+        self.popups.handle(&self.session)?;
+        if let Some(policy) = &self.status_policy
+            && let Some(message) = self.session.last_message()?
+        {
+            policy.check(&message)?;
+        }
+        Ok(())
+    }
+
+    /// Press a button.
+    pub fn press(&self, id: &str) -> crate::Result<(), Error> {
+        let button: GuiButton = find_as(&self.session, id)?;
+        button.press()?;
+        self.after_action()
+    }
+
+    /// Send a virtual key to the active window.
+    pub fn send_vkey(&self, key: VKey) -> crate::Result<(), Error> {
+        let window = self.session.active_window()?;
+        GuiFrameWindow::from(window.get_idispatch().clone()).send_vkey(key)?;
+        self.after_action()
+    }
+
+    /// Start a transaction.
+    pub fn start_transaction(&self, transaction: &str) -> crate::Result<(), Error> {
+        self.session.start_transaction(transaction.to_string())?;
+        self.after_action()
+    }
+
+    /// Submit an OK-code in the command field of the main window.
+    pub fn execute(&self, code: OkCode) -> crate::Result<(), Error> {
+        self.session.execute(code)?;
+        self.after_action()
+    }
+
+    /// Set the text of a component. The checks only run if the component flushes, that is, if
+    /// changing it causes a roundtrip to the server.
+    pub fn set_text(&self, id: &str, text: &str) -> crate::Result<(), Error> {
+        let component = self.session.find_by_id(id.to_string())?;
+        if self.validate_input {
+            component.check_value(&SapValue::Text(text.to_string()))?;
+        }
+        // The component may be stale once its text is set, so read this first.
+        let flushes = flushes(&component);
+        GuiVComponent::from(component.get_idispatch().clone()).set_text(text.to_string())?;
+        if flushes {
+            self.after_action()?;
+        }
+        Ok(())
+    }
+}
+
+impl Deref for ManagedSession {
+    type Target = GuiSession;
+
+    fn deref(&self) -> &Self::Target {
+        &self.session
+    }
+}
+
+impl From<GuiSession> for ManagedSession {
+    fn from(value: GuiSession) -> Self {
+        ManagedSession::new(value)
+    }
+}
+
+/// Whether changing a component causes a roundtrip. If the `Flushing` property cannot be read, the
+/// component is assumed to flush, so that the checks run rather than being skipped.
+fn flushes(component: &GuiComponent) -> bool {
+    match component
+        .get_idispatch()
+        .get("Flushing")
+        .and_then(|v| v.variant_into())
+    {
+        Ok(flushes) => flushes,
+        Err(e) => {
+            tracing::debug!("Could not read Flushing, assuming the component flushes: {e}");
+            true
+        }
+    }
+}
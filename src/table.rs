@@ -0,0 +1,132 @@
+/// The ABAP data type of a column.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum DataType {
+    /// Character data (`C`, `CHAR`, `CUKY`, `UNIT`, ...).
+    Char,
+    /// A numeric character string (`N`, `NUMC`).
+    Numc,
+    /// A packed decimal number (`P`, `DEC`, `CURR`, `QUAN`).
+    Packed,
+    /// An integer (`I`, `INT4`, ...).
+    Integer,
+    /// A floating point number (`F`, `FLTP`).
+    Float,
+    /// A date (`D`, `DATS`).
+    Date,
+    /// A time (`T`, `TIMS`).
+    Time,
+    /// A variable length string (`g`, `STRING`).
+    String,
+    /// Any other type, with the code reported by SAP GUI.
+    Other(std::string::String),
+}
+
+impl DataType {
+    /// Interpret a type code, either an ABAP type or a dictionary data type.
+    pub fn from_code(code: &str) -> Self {
+        match code.trim() {
+            "C" | "CHAR" | "CLNT" | "CUKY" | "UNIT" | "LANG" | "LCHR" => DataType::Char,
+            "N" | "NUMC" | "ACCP" => DataType::Numc,
+            "P" | "DEC" | "CURR" | "QUAN" => DataType::Packed,
+            "I" | "b" | "s" | "8" | "INT1" | "INT2" | "INT4" | "INT8" => DataType::Integer,
+            "F" | "FLTP" => DataType::Float,
+            "D" | "DATS" => DataType::Date,
+            "T" | "TIMS" => DataType::Time,
+            "g" | "STRING" | "SSTRING" => DataType::String,
+            other => DataType::Other(other.to_string()),
+        }
+    }
+
+    /// Whether values of this type are numbers.
+    pub fn is_numeric(&self) -> bool {
+        matches!(self, DataType::Packed | DataType::Integer | DataType::Float)
+    }
+}
+
+/// A column of an extracted [`Table`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Column {
+    /// The technical name of the column, e.g. `MATNR`.
+    pub name: String,
+    /// The title of the column, as displayed.
+    pub title: String,
+    /// The data type of the column.
+    pub data_type: DataType,
+    /// Whether the column is part of the key.
+    pub key: bool,
+    /// Whether the cells of the column are check boxes. Their values are `X` when checked.
+    pub checkbox: bool,
+}
+
+/// A row of an extracted [`Table`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Row {
+    /// The value of each cell, in the same order as the columns of the table.
+    pub cells: Vec<String>,
+    /// Whether the row was selected.
+    pub selected: bool,
+    /// The totals level of the row. Zero for normal rows, otherwise the row shows a total or subtotal.
+    pub total_level: i32,
+}
+
+impl Row {
+    /// Whether this row shows a total or subtotal.
+    pub fn is_total(&self) -> bool {
+        self.total_level != 0
+    }
+}
+
+/// The contents of a grid or table control, read into memory.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Table {
+    /// The columns of the table, in display order.
+    pub columns: Vec<Column>,
+    /// The rows of the table.
+    pub rows: Vec<Row>,
+}
+
+impl Table {
+    /// Find the index of a column by its technical name or, failing that, its title.
+    pub fn column_index(&self, name: &str) -> Option<usize> {
+        self.columns
+            .iter()
+            .position(|c| c.name.eq_ignore_ascii_case(name))
+            .or_else(|| {
+                self.columns
+                    .iter()
+                    .position(|c| c.title.trim().eq_ignore_ascii_case(name.trim()))
+            })
+    }
+
+    /// Get the value of a cell, by row index and column name or title.
+    pub fn cell(&self, row: usize, column: &str) -> Option<&str> {
+        let column = self.column_index(column)?;
+        self.rows.get(row)?.cells.get(column).map(String::as_str)
+    }
+
+    /// The number of rows.
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Whether the table has no rows.
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+}
+
+/// Parse a row selection as reported by SAP GUI, e.g. `1,3-5`.
+pub(crate) fn parse_row_ranges(ranges: &str) -> Vec<i32> {
+    let mut rows = vec![];
+    for range in ranges.split(',').map(str::trim).filter(|r| !r.is_empty()) {
+        match range.split_once('-') {
+            Some((from, to)) => {
+                if let (Ok(from), Ok(to)) = (from.trim().parse::<i32>(), to.trim().parse::<i32>()) {
+                    rows.extend(from..=to);
+                }
+            }
+            None => rows.extend(range.parse::<i32>()),
+        }
+    }
+    rows
+}
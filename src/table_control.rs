@@ -0,0 +1,283 @@
+use com_shim::{HasIDispatch, IsA};
+
+use crate::Error;
+use crate::table::*;
+use crate::types::*;
+use crate::value::*;
+
+/// Rows written to a table control by [`GuiTableControl::append_rows`].
+pub struct AppendedRows {
+    /// The table control, found again after writing.
+    pub table: GuiTableControl,
+    /// The absolute rows that were written.
+    pub rows: std::ops::Range<i32>,
+}
+
+impl GuiTableControl {
+    /// Read every row of this table control into a [`Table`].
+    ///
+    /// Only the visible rows of a table control exist at any time, so this scrolls through the
+    /// table, finding it again after each scroll, and returns the scrollbar to where it started.
+    /// This handle (and any cells taken from it) will be stale afterwards, so find the table again
+    /// to keep working with it.
+    pub fn extract(&self) -> crate::Result<Table> {
+        let columns = self.column_info()?;
+        let row_count = self.row_count()?;
+        let scrollbar = self.vertical_scroll()?;
+        let start = scrollbar.position()?;
+        tracing::debug!("Extracting {row_count} rows from table control.");
+
+        let location = TableLocation::of(self)?;
+        let mut table = GuiTableControl::from(self.get_idispatch().clone());
+        let mut rows = Vec::with_capacity(row_count.max(0) as usize);
+        while (rows.len() as i32) < row_count {
+            let next = rows.len() as i32;
+            table = table.scroll_and_find(&location, next)?;
+            // Near the end, the scrollbar stops short of the requested row.
+            let offset = next - table.vertical_scroll()?.position()?;
+            let visible = table.visible_row_count()?;
+            if offset >= visible {
+                break;
+            }
+            for row in offset..visible.min(offset + row_count - next) {
+                let mut cells = Vec::with_capacity(columns.len());
+                for column in 0..columns.len() as i32 {
+                    cells.push(cell_text(&table.get_cell(row, column)?)?);
+                }
+                let absolute = next + row - offset;
+                rows.push(Row {
+                    cells,
+                    selected: table.get_absolute_row(absolute)?.selected()?,
+                    total_level: 0,
+                });
+            }
+        }
+        table.scroll_and_find(&location, start)?;
+
+        Ok(Table { columns, rows })
+    }
+
+    /// The columns of this table, named after the fields of their cells.
+    pub(crate) fn column_info(&self) -> crate::Result<Vec<Column>> {
+        let mut columns = vec![];
+        for column in self.columns()?.components()? {
+            let column = GuiTableColumn::from(column.get_idispatch().clone());
+            let title = column.title()?;
+            let (name, checkbox) = match column.element_at(0) {
+                Ok(cell) => (cell.name()?, cell.r_type()? == "GuiCheckBox"),
+                Err(_) => (title.clone(), false),
+            };
+            columns.push(Column {
+                name,
+                title,
+                data_type: DataType::Char,
+                key: false,
+                checkbox,
+            });
+        }
+        Ok(columns)
+    }
+
+    /// Write a value to a cell, given its absolute row and its column by field name or title,
+    /// scrolling the row into view first if needed. Returns the table found again, as scrolling
+    /// makes this handle stale.
+    pub fn set_cell<V: Into<SapValue>>(
+        &self,
+        row: i32,
+        column: &str,
+        value: V,
+    ) -> crate::Result<GuiTableControl, Error> {
+        let column = self.column_index(column)?;
+        let location = TableLocation::of(self)?;
+        self.set_cell_at(&location, row, column, value.into())
+    }
+
+    /// Write rows of values after the last row that has any text, with the values of each row in
+    /// the order of the columns named by field name or title. A value may be `None` to leave its
+    /// cell alone.
+    ///
+    /// The table is scrolled as the rows are filled in, so the values on each page are passed to
+    /// the application before the next, which lets tables that only grow as input is accepted
+    /// make room for more rows. Returns the table found again, as scrolling makes this handle
+    /// stale. If a row cannot be written, the error is [`Error::Append`], saying how many rows
+    /// were written before it.
+    pub fn append_rows<S, R, C, V>(
+        &self,
+        columns: &[S],
+        rows: R,
+    ) -> crate::Result<AppendedRows, Error>
+    where
+        S: AsRef<str>,
+        R: IntoIterator<Item = C>,
+        C: IntoIterator<Item = Option<V>>,
+        V: Into<SapValue>,
+    {
+        let columns = columns
+            .iter()
+            .map(|column| self.column_index(column.as_ref()))
+            .collect::<crate::Result<Vec<_>, Error>>()?;
+        let location = TableLocation::of(self)?;
+        let (mut table, first) = self.first_free_row(&location)?;
+        tracing::debug!("Appending rows to table control from row {first}.");
+        let mut row = first;
+        for values in rows {
+            for (&column, value) in columns.iter().zip(values) {
+                if let Some(value) = value {
+                    table = table
+                        .set_cell_at(&location, row, column, value.into())
+                        .map_err(|e| Error::Append {
+                            first_row: first,
+                            written: (row - first) as usize,
+                            source: Box::new(e),
+                        })?;
+                }
+            }
+            row += 1;
+        }
+        Ok(AppendedRows {
+            table,
+            rows: first..row,
+        })
+    }
+
+    /// Find the index of a column by the field name of its cells or by its title.
+    fn column_index(&self, column: &str) -> crate::Result<i32, Error> {
+        let columns = self.column_info()?;
+        columns
+            .iter()
+            .position(|c| c.name == column)
+            .or_else(|| columns.iter().position(|c| c.title.trim() == column.trim()))
+            .map(|index| index as i32)
+            .ok_or_else(|| Error::NoSuchColumn {
+                column: column.to_string(),
+            })
+    }
+
+    /// Write a value to a cell by absolute row and column index, scrolling if the row is not
+    /// visible.
+    ///
+    /// Scrolling passes the input on the screen to the application, which may add rows to the
+    /// table, so rows past the end are scrolled towards until the table stops growing.
+    fn set_cell_at(
+        &self,
+        location: &TableLocation,
+        row: i32,
+        column: i32,
+        value: SapValue,
+    ) -> crate::Result<GuiTableControl, Error> {
+        let mut table = GuiTableControl::from(self.get_idispatch().clone());
+        loop {
+            let scrollbar = table.vertical_scroll()?;
+            let position = scrollbar.position()?;
+            if row >= position && row < position + table.visible_row_count()? {
+                break;
+            }
+            // The scrollbar cannot go past its maximum until the table grows.
+            let target = row.min(scrollbar.maximum()?).max(0);
+            if row < 0 || target == position {
+                return Err(Error::RowOutOfRange {
+                    row,
+                    row_count: table.row_count()?,
+                });
+            }
+            table = table.scroll_and_find(location, target)?;
+        }
+        let offset = row - table.vertical_scroll()?.position()?;
+        table.get_cell(offset, column)?.set_value(value)?;
+        // Setting some cells, such as combo boxes, is a roundtrip.
+        Ok(location.find()?)
+    }
+
+    /// Find the row after the last one with any text, scrolling through the table as needed.
+    /// Returns the table found again along with the row.
+    fn first_free_row(&self, location: &TableLocation) -> crate::Result<(GuiTableControl, i32)> {
+        let row_count = self.row_count()?;
+        let column_count = self.columns()?.count()?;
+        let mut table = GuiTableControl::from(self.get_idispatch().clone());
+        let mut free = 0;
+        let mut next = 0;
+        while next < row_count {
+            table = table.scroll_and_find(location, next)?;
+            let offset = next - table.vertical_scroll()?.position()?;
+            let visible = table.visible_row_count()?;
+            if offset >= visible {
+                break;
+            }
+            for row in offset..visible.min(offset + row_count - next) {
+                for column in 0..column_count {
+                    if !cell_text(&table.get_cell(row, column)?)?.trim().is_empty() {
+                        free = next + row - offset + 1;
+                        break;
+                    }
+                }
+            }
+            next += visible - offset;
+        }
+        Ok((table, free))
+    }
+
+    fn vertical_scroll(&self) -> crate::Result<GuiScrollbar> {
+        Ok(GuiScrollbar::from(
+            self.vertical_scrollbar()?.get_idispatch().clone(),
+        ))
+    }
+
+    /// Scroll so that a row is the first visible, then find the table again from where it was
+    /// before, as scrolling is a roundtrip.
+    pub(crate) fn scroll_and_find(
+        &self,
+        location: &TableLocation,
+        row: i32,
+    ) -> crate::Result<GuiTableControl> {
+        let scrollbar = self.vertical_scroll()?;
+        if scrollbar.position()? != row {
+            scrollbar.set_position(row)?;
+        }
+        location.find()
+    }
+}
+
+/// Where a table control is, read while its handle is current so that it can be found again after
+/// a roundtrip makes the handle stale.
+pub(crate) struct TableLocation {
+    session: GuiSession,
+    id: String,
+    window: String,
+    name: String,
+}
+
+impl TableLocation {
+    /// Read where a table control is.
+    pub(crate) fn of(table: &GuiTableControl) -> crate::Result<Self> {
+        let component: GuiComponent = table.upcast();
+        Ok(TableLocation {
+            session: component.session()?,
+            id: table.id()?,
+            window: table.parent_frame()?.id()?,
+            name: table.name()?,
+        })
+    }
+
+    /// Find the table control. If its ID no longer resolves, the table with the same name is
+    /// looked up in its window.
+    pub(crate) fn find(&self) -> crate::Result<GuiTableControl> {
+        if let Ok(table) = find_as(&self.session, &self.id) {
+            return Ok(table);
+        }
+        let window: GuiVContainer = find_as(&self.session, &self.window)?;
+        tracing::debug!("Table control {} moved, finding it by name.", self.id);
+        let table = window.find_by_name(self.name.clone(), "GuiTableControl".to_string())?;
+        Ok(GuiTableControl::from(table.get_idispatch().clone()))
+    }
+}
+
+/// The text of a table control cell. Check boxes and radio buttons are `X` when selected.
+pub(crate) fn cell_text(cell: &GuiComponent) -> crate::Result<String> {
+    let flag = |selected: bool| if selected { "X" } else { "" }.to_string();
+    Ok(match cell.r_type()?.as_str() {
+        "GuiCheckBox" => flag(GuiCheckBox::from(cell.get_idispatch().clone()).selected()?),
+        "GuiRadioButton" => flag(GuiRadioButton::from(cell.get_idispatch().clone()).selected()?),
+        "GuiComboBox" => GuiComboBox::from(cell.get_idispatch().clone()).value()?,
+        _ => GuiVComponent::from(cell.get_idispatch().clone()).text()?,
+    })
+}
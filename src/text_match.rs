@@ -0,0 +1,64 @@
+use std::fmt;
+
+use regex::Regex;
+
+/// A way of matching text shown in SAP GUI, such as a window title or a node text.
+#[derive(Clone, Debug)]
+pub enum TextMatch {
+    /// The text must be exactly equal, ignoring surrounding whitespace.
+    Exact(String),
+    /// The text must be equal ignoring case and surrounding whitespace.
+    CaseInsensitive(String),
+    /// The text must contain this.
+    Contains(String),
+    /// The text must match this regular expression.
+    Regex(Regex),
+}
+
+impl TextMatch {
+    /// Match text against a regular expression.
+    pub fn regex(pattern: &str) -> Result<Self, regex::Error> {
+        Ok(TextMatch::Regex(Regex::new(pattern)?))
+    }
+
+    /// Check if some text matches.
+    pub fn matches(&self, text: &str) -> bool {
+        match self {
+            TextMatch::Exact(expected) => text.trim() == expected.trim(),
+            TextMatch::CaseInsensitive(expected) => {
+                text.trim().to_lowercase() == expected.trim().to_lowercase()
+            }
+            TextMatch::Contains(expected) => text.contains(expected.as_str()),
+            TextMatch::Regex(re) => re.is_match(text),
+        }
+    }
+}
+
+impl From<&str> for TextMatch {
+    fn from(value: &str) -> Self {
+        TextMatch::Exact(value.to_string())
+    }
+}
+
+impl From<String> for TextMatch {
+    fn from(value: String) -> Self {
+        TextMatch::Exact(value)
+    }
+}
+
+impl From<Regex> for TextMatch {
+    fn from(value: Regex) -> Self {
+        TextMatch::Regex(value)
+    }
+}
+
+impl fmt::Display for TextMatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TextMatch::Exact(text) => write!(f, "\"{text}\""),
+            TextMatch::CaseInsensitive(text) => write!(f, "\"{text}\" (any case)"),
+            TextMatch::Contains(text) => write!(f, "containing \"{text}\""),
+            TextMatch::Regex(re) => write!(f, "/{re}/"),
+        }
+    }
+}
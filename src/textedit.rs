@@ -0,0 +1,174 @@
+use regex::Regex;
+use windows::Win32::Foundation::E_FAIL;
+
+use crate::types::*;
+
+/// A place in the text of a [`GuiTextedit`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TextPosition {
+    /// The line, counting from zero.
+    pub line: usize,
+    /// The character within the line, counting from zero.
+    pub column: usize,
+}
+
+impl GuiTextedit {
+    /// Read every line of this editor.
+    pub fn lines(&self) -> crate::Result<Vec<String>> {
+        (0..self.line_count()?)
+            .map(|line| self.get_line_text(line))
+            .collect()
+    }
+
+    /// Read the whole text of this editor, with lines separated by `\n`.
+    pub fn full_text(&self) -> crate::Result<String> {
+        Ok(self.lines()?.join("\n"))
+    }
+
+    /// Replace the whole text of this editor. Lines may be separated by `\n` or `\r\n`.
+    pub fn set_full_text(&self, text: &str) -> crate::Result<()> {
+        self.set_text(to_control_text(text))
+    }
+
+    /// Find every occurrence of some text, which must not span lines.
+    pub fn find(&self, needle: &str) -> crate::Result<Vec<TextPosition>> {
+        let mut found = vec![];
+        if needle.is_empty() {
+            return Ok(found);
+        }
+        for (line, text) in self.lines()?.iter().enumerate() {
+            for (index, _) in text.match_indices(needle) {
+                found.push(TextPosition {
+                    line,
+                    column: text[..index].chars().count(),
+                });
+            }
+        }
+        Ok(found)
+    }
+
+    /// Replace every occurrence of some text, returning how many were replaced. The text is only
+    /// written back if something was replaced.
+    pub fn replace(&self, from: &str, to: &str) -> crate::Result<usize> {
+        if from.is_empty() {
+            return Ok(0);
+        }
+        let text = self.full_text()?;
+        let count = text.matches(from).count();
+        if count > 0 {
+            self.set_full_text(&text.replace(from, to))?;
+        }
+        Ok(count)
+    }
+
+    /// Replace every match of a regular expression, returning how many were replaced. The
+    /// replacement may refer to groups as in [`Regex::replace_all`].
+    pub fn replace_regex(&self, pattern: &Regex, replacement: &str) -> crate::Result<usize> {
+        let text = self.full_text()?;
+        let count = pattern.find_iter(&text).count();
+        if count > 0 {
+            self.set_full_text(&pattern.replace_all(&text, replacement))?;
+        }
+        Ok(count)
+    }
+
+    /// Read the parts of the text that can be changed, to edit them without touching the
+    /// protected parts between them.
+    pub fn unprotected_parts(&self) -> crate::Result<UnprotectedParts<'_>> {
+        let parts = (0..self.number_of_unprotected_text_parts()?)
+            .map(|part| Ok(from_control_text(&self.get_unprotected_text_part(part)?)))
+            .collect::<crate::Result<Vec<_>>>()?;
+        Ok(UnprotectedParts {
+            textedit: self,
+            changed: vec![false; parts.len()],
+            parts,
+        })
+    }
+}
+
+/// The unprotected parts of the text of a [`GuiTextedit`], edited in memory and written back
+/// with [`UnprotectedParts::save`]. Lines are separated by `\n`.
+pub struct UnprotectedParts<'a> {
+    textedit: &'a GuiTextedit,
+    parts: Vec<String>,
+    changed: Vec<bool>,
+}
+
+impl UnprotectedParts<'_> {
+    /// The number of unprotected parts.
+    pub fn len(&self) -> usize {
+        self.parts.len()
+    }
+
+    /// Whether the whole text is protected.
+    pub fn is_empty(&self) -> bool {
+        self.parts.is_empty()
+    }
+
+    /// The text of a part.
+    pub fn get(&self, part: usize) -> Option<&str> {
+        self.parts.get(part).map(String::as_str)
+    }
+
+    /// The text of every part.
+    pub fn parts(&self) -> &[String] {
+        &self.parts
+    }
+
+    /// Change the text of a part. Returns `false` if there is no such part.
+    pub fn set<S: Into<String>>(&mut self, part: usize, text: S) -> bool {
+        let Some(current) = self.parts.get_mut(part) else {
+            return false;
+        };
+        *current = text.into();
+        self.changed[part] = true;
+        true
+    }
+
+    /// Replace every occurrence of some text in every part, returning how many were replaced.
+    pub fn replace(&mut self, from: &str, to: &str) -> usize {
+        if from.is_empty() {
+            return 0;
+        }
+        let mut count = 0;
+        for (part, changed) in self.parts.iter_mut().zip(&mut self.changed) {
+            let matches = part.matches(from).count();
+            if matches > 0 {
+                *part = part.replace(from, to);
+                *changed = true;
+                count += matches;
+            }
+        }
+        count
+    }
+
+    /// Write the parts that were changed back to the editor.
+    pub fn save(self) -> crate::Result<()> {
+        for (index, part) in self.parts.iter().enumerate() {
+            if !self.changed[index] {
+                continue;
+            }
+            let index = index as i32;
+            if !self
+                .textedit
+                .set_unprotected_text_part(index, to_control_text(part))?
+            {
+                return Err(windows::core::Error::new(
+                    E_FAIL,
+                    format!("unprotected text part {index} could not be changed").into(),
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Convert text with `\n` or `\r\n` line breaks to the `\r` line breaks used by the control.
+fn to_control_text(text: &str) -> String {
+    text.replace("\r\n", "\r").replace('\n', "\r")
+}
+
+/// Convert text from the control to use `\n` line breaks.
+fn from_control_text(text: &str) -> String {
+    text.replace("\r\n", "\n").replace('\r', "\n")
+}
@@ -0,0 +1,363 @@
+use com_shim::{HasIDispatch, IDispatchExt, VARIANT, VariantTypeExt};
+
+use crate::Error;
+use crate::text_match::TextMatch;
+use crate::types::*;
+
+/// The kind of a tree, which decides what items its nodes have.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum TreeType {
+    /// Each node has only its text.
+    Simple,
+    /// Each node has a list of items after its text.
+    List,
+    /// Each node has an item in each column of the tree.
+    Column,
+}
+
+impl TreeType {
+    /// Interpret the tree type reported by SAP GUI.
+    pub fn from_code(code: i32) -> Option<Self> {
+        match code {
+            0 => Some(TreeType::Simple),
+            1 => Some(TreeType::List),
+            2 => Some(TreeType::Column),
+            _ => None,
+        }
+    }
+}
+
+/// A column of a column tree.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct TreeColumn {
+    /// The technical name of the column.
+    pub name: String,
+    /// The title of the column, as displayed.
+    pub title: String,
+}
+
+/// A node of a [`TreeSnapshot`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct TreeNode {
+    /// The key of the node, used to refer to it in the tree.
+    pub key: String,
+    /// The text of the node.
+    pub text: String,
+    /// The path of the node, as reported by SAP GUI, e.g. `2\1\3`.
+    pub path: String,
+    /// The level of the node in the hierarchy, starting from 1 for top level nodes.
+    pub level: i32,
+    /// Whether the node is a folder.
+    pub folder: bool,
+    /// Whether the node was expanded. Folders that are expanded by the snapshot count as expanded.
+    pub expanded: bool,
+    /// The texts of the items of the node: one per column in a column tree, or the items
+    /// after the node text in a list tree.
+    pub items: Vec<String>,
+    /// The children of the node. Empty for folders that were collapsed and not expanded.
+    pub children: Vec<TreeNode>,
+}
+
+impl TreeNode {
+    /// Find a descendant by the texts of the nodes leading to it, starting with a child of this
+    /// node.
+    pub fn find<M: Into<TextMatch>, P: IntoIterator<Item = M>>(
+        &self,
+        path: P,
+    ) -> Option<&TreeNode> {
+        find_in(&self.children, path)
+    }
+
+    /// This node and all its descendants, depth first.
+    pub fn descendants(&self) -> Vec<&TreeNode> {
+        let mut nodes = vec![self];
+        for child in &self.children {
+            nodes.extend(child.descendants());
+        }
+        nodes
+    }
+}
+
+/// The contents of a [`GuiTree`], read into memory.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct TreeSnapshot {
+    /// The kind of tree.
+    pub tree_type: TreeType,
+    /// The columns of a column tree. Empty for other trees.
+    pub columns: Vec<TreeColumn>,
+    /// The top level nodes.
+    pub roots: Vec<TreeNode>,
+}
+
+impl TreeSnapshot {
+    /// Find a node by the texts of the nodes leading to it, starting with a top level node, e.g.
+    /// `["Financial Accounting", "General Ledger"]`.
+    pub fn find<M: Into<TextMatch>, P: IntoIterator<Item = M>>(
+        &self,
+        path: P,
+    ) -> Option<&TreeNode> {
+        find_in(&self.roots, path)
+    }
+
+    /// Find a node by its key.
+    pub fn node(&self, key: &str) -> Option<&TreeNode> {
+        self.nodes().into_iter().find(|n| n.key == key)
+    }
+
+    /// Every node of the tree, depth first.
+    pub fn nodes(&self) -> Vec<&TreeNode> {
+        self.roots.iter().flat_map(TreeNode::descendants).collect()
+    }
+
+    /// Convert this snapshot to JSON.
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("tree snapshots are valid JSON")
+    }
+}
+
+fn find_in<M: Into<TextMatch>, P: IntoIterator<Item = M>>(
+    nodes: &[TreeNode],
+    path: P,
+) -> Option<&TreeNode> {
+    let mut path = path.into_iter().map(Into::into);
+    let first = path.next()?;
+    let mut node = nodes.iter().find(|n| first.matches(&n.text))?;
+    for segment in path {
+        node = node.children.iter().find(|n| segment.matches(&n.text))?;
+    }
+    Some(node)
+}
+
+/// Options for [`GuiTree::snapshot`].
+#[derive(Clone, Debug, Default)]
+pub struct SnapshotOptions {
+    expand: bool,
+    max_depth: Option<usize>,
+}
+
+impl SnapshotOptions {
+    /// Create the default options: read the nodes that are loaded, without expanding anything.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set whether collapsed folders are expanded to read their children. Expanding a folder may
+    /// load its children from the server, so this can be slow for large trees.
+    pub fn expand(mut self, expand: bool) -> Self {
+        self.expand = expand;
+        self
+    }
+
+    /// Only read nodes down to a depth, where top level nodes have depth 1.
+    pub fn max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = Some(depth);
+        self
+    }
+}
+
+impl GuiTree {
+    /// The keys of every node in this tree.
+    pub fn all_node_keys(&self) -> crate::Result<Vec<String>> {
+        self.collect_strings("GetAllNodeKeys", vec![])
+    }
+
+    /// The keys of every node in this tree, in display order.
+    pub fn node_keys(&self) -> crate::Result<Vec<String>> {
+        self.collect_strings("GetNodesCol", vec![])
+    }
+
+    /// The keys of the direct children of a node.
+    pub fn sub_node_keys<S: Into<String>>(&self, node_key: S) -> crate::Result<Vec<String>> {
+        self.collect_strings(
+            "GetSubNodesCol",
+            vec![VARIANT::variant_from(node_key.into())],
+        )
+    }
+
+    /// The keys of the selected nodes.
+    pub fn selected_node_keys(&self) -> crate::Result<Vec<String>> {
+        self.collect_strings("GetSelectedNodes", vec![])
+    }
+
+    /// The technical names of the columns of a column or list tree.
+    pub fn column_names(&self) -> crate::Result<Vec<String>> {
+        self.collect_strings("GetColumnNames", vec![])
+    }
+
+    /// The header texts of the columns of a column or list tree.
+    pub fn column_headers(&self) -> crate::Result<Vec<String>> {
+        self.collect_strings("GetColumnHeaders", vec![])
+    }
+
+    /// The titles of the columns of a column or list tree.
+    pub fn column_titles(&self) -> crate::Result<Vec<String>> {
+        self.collect_strings("GetColumnTitles", vec![])
+    }
+
+    /// The item texts of a column for every node, in the order of [`GuiTree::node_keys`].
+    pub fn column_items<S: Into<String>>(&self, column_name: S) -> crate::Result<Vec<String>> {
+        self.collect_strings(
+            "GetColumnCol",
+            vec![VARIANT::variant_from(column_name.into())],
+        )
+    }
+
+    /// The header texts of the items of a node in a list tree.
+    pub fn node_item_headers<S: Into<String>>(&self, node_key: S) -> crate::Result<Vec<String>> {
+        self.collect_strings(
+            "GetNodeItemHeaders",
+            vec![VARIANT::variant_from(node_key.into())],
+        )
+    }
+
+    /// Find a node by the texts of the nodes leading to it, starting with a top level node, e.g.
+    /// `&["Financial Accounting", "General Ledger", "Master Records"]`, and return its key.
+    /// Collapsed folders along the way are expanded. Segments may be plain text, which must
+    /// match exactly, or any other [`TextMatch`]. If a node along the way is not a folder or has
+    /// no children, the next segment is reported as not found.
+    pub fn find_by_text_path<M: Clone + Into<TextMatch>>(
+        &self,
+        path: &[M],
+    ) -> crate::Result<String, Error> {
+        let mut parent: Option<String> = None;
+        for (index, segment) in path.iter().enumerate() {
+            let segment: TextMatch = segment.clone().into();
+            let not_found = || Error::TreePath {
+                index,
+                segment: segment.to_string(),
+            };
+            let first_path = match &parent {
+                Some(parent) => {
+                    if !self.is_folder(parent.clone())? {
+                        return Err(not_found());
+                    }
+                    if !self.is_folder_expanded(parent.clone())? {
+                        tracing::debug!("Expanding tree node {parent}.");
+                        self.expand_node(parent.clone())?;
+                    }
+                    if self.get_node_children_count(parent.clone())? == 0 {
+                        return Err(not_found());
+                    }
+                    format!("{}\\1", self.get_node_path_by_key(parent.clone())?)
+                }
+                None => "1".to_string(),
+            };
+
+            let mut candidate = non_empty(self.get_node_key_by_path(first_path)?);
+            while let Some(key) = &candidate {
+                if segment.matches(&self.get_node_text_by_key(key.clone())?) {
+                    break;
+                }
+                candidate = non_empty(self.get_next_node_key(key.clone())?);
+            }
+            parent = Some(candidate.ok_or_else(not_found)?);
+        }
+        parent.ok_or_else(|| Error::TreePath {
+            index: 0,
+            segment: "(empty path)".to_string(),
+        })
+    }
+
+    /// Find a node by the texts of the nodes leading to it, as in [`GuiTree::find_by_text_path`],
+    /// then select it and return its key.
+    pub fn select_by_text_path<M: Clone + Into<TextMatch>>(
+        &self,
+        path: &[M],
+    ) -> crate::Result<String, Error> {
+        let key = self.find_by_text_path(path)?;
+        self.select_node(key.clone())?;
+        Ok(key)
+    }
+
+    /// Read the nodes of this tree into a [`TreeSnapshot`].
+    pub fn snapshot(&self, options: &SnapshotOptions) -> crate::Result<TreeSnapshot> {
+        let tree_type = TreeType::from_code(self.get_tree_type()?).unwrap_or(TreeType::Simple);
+        let columns = if tree_type == TreeType::Column {
+            self.column_names()?
+                .into_iter()
+                .map(|name| {
+                    Ok(TreeColumn {
+                        title: self.get_column_title_from_name(name.clone())?,
+                        name,
+                    })
+                })
+                .collect::<crate::Result<Vec<_>>>()?
+        } else {
+            vec![]
+        };
+
+        let mut roots = vec![];
+        for key in self.all_node_keys()? {
+            if self.get_parent(key.clone())?.trim().is_empty() {
+                roots.push(self.snapshot_node(key, 1, tree_type, &columns, options)?);
+            }
+        }
+        Ok(TreeSnapshot {
+            tree_type,
+            columns,
+            roots,
+        })
+    }
+
+    fn snapshot_node(
+        &self,
+        key: String,
+        depth: usize,
+        tree_type: TreeType,
+        columns: &[TreeColumn],
+        options: &SnapshotOptions,
+    ) -> crate::Result<TreeNode> {
+        let folder = self.is_folder(key.clone())?;
+        let mut expanded = folder && self.is_folder_expanded(key.clone())?;
+        let within_depth = options.max_depth.is_none_or(|max| depth < max);
+        if folder && !expanded && options.expand && within_depth {
+            tracing::debug!("Expanding tree node {key}.");
+            self.expand_node(key.clone())?;
+            expanded = true;
+        }
+
+        let items = match tree_type {
+            TreeType::Simple => vec![],
+            TreeType::Column => columns
+                .iter()
+                .map(|c| self.get_item_text(key.clone(), c.name.clone()))
+                .collect::<crate::Result<_>>()?,
+            TreeType::List => (1..=self.get_list_tree_node_item_count(key.clone())?)
+                .map(|i| self.get_item_text(key.clone(), i.to_string()))
+                .collect::<crate::Result<_>>()?,
+        };
+
+        let mut children = vec![];
+        if expanded && within_depth {
+            for child in self.sub_node_keys(key.clone())? {
+                children.push(self.snapshot_node(child, depth + 1, tree_type, columns, options)?);
+            }
+        }
+
+        Ok(TreeNode {
+            text: self.get_node_text_by_key(key.clone())?,
+            path: self.get_node_path_by_key(key.clone())?,
+            level: self.get_hierarchy_level(key.clone())?,
+            folder,
+            expanded,
+            items,
+            children,
+            key,
+        })
+    }
+
+    /// Call a method returning a collection of strings.
+    fn collect_strings(&self, method: &str, args: Vec<VARIANT>) -> crate::Result<Vec<String>> {
+        collection_strings(&self.get_idispatch().call(method, args)?)
+    }
+}
+
+/// A node key, or `None` if it is blank because there is no such node.
+fn non_empty(key: String) -> Option<String> {
+    (!key.trim().is_empty()).then_some(key)
+}
@@ -1,5 +1,7 @@
 use com_shim::{com_shim, IDispatchExt, VariantTypeExt};
-use windows::{Win32::System::Com::*, Win32::System::Variant::*, core::*};
+use windows::{
+    Win32::Foundation::E_NOINTERFACE, Win32::System::Com::*, Win32::System::Variant::*, core::*,
+};
 
 /// A wrapper over the SAP scripting engine, equivalent to CSapROTWrapper.
 pub struct SAPWrapper {
@@ -97,6 +99,53 @@ impl GuiComponent {
             None
         }
     }
+
+    /// The type of this component, using the subtype for shells, e.g. `GuiCTextField` or `GridView`.
+    pub fn kind(&self) -> crate::Result<String> {
+        let kind = self.r_type()?;
+        if kind == "GuiShell" {
+            GuiShell::from(self.inner.clone()).sub_type()
+        } else {
+            Ok(kind)
+        }
+    }
+
+    /// Find the nearest ancestor of this component of a type, e.g. `GuiSession`.
+    pub fn ancestor(&self, kind: &str) -> crate::Result<GuiComponent> {
+        let mut component = self.parent()?;
+        while component.r_type()? != kind {
+            component = component.parent()?;
+        }
+        Ok(component)
+    }
+
+    /// Find the session this component belongs to.
+    pub fn session(&self) -> crate::Result<GuiSession> {
+        Ok(GuiSession::from(self.ancestor("GuiSession")?.inner))
+    }
+
+    /// Find the application this component belongs to.
+    pub fn application(&self) -> crate::Result<GuiApplication> {
+        Ok(GuiApplication::from(self.ancestor("GuiApplication")?.inner))
+    }
+}
+
+/// Find a component by ID within a container and downcast it, failing if it is of another type.
+pub(crate) fn find_as<Tgt, C>(container: &C, id: &str) -> crate::Result<Tgt>
+where
+    C: GuiContainerExt,
+    Tgt: HasSAPType + From<IDispatch>,
+{
+    container
+        .find_by_id(id.to_string())?
+        .downcast()
+        .ok_or_else(|| {
+            let expected = Tgt::sap_subtype().unwrap_or_else(|| Tgt::sap_type());
+            Error::new(
+                E_NOINTERFACE,
+                HSTRING::from(format!("component {id} is not a {expected}")),
+            )
+        })
 }
 
 com_shim! {
@@ -227,7 +276,7 @@ com_shim! {
         r#Type: String,
         TypeAsNumber: i32,
 
-        // TODO fn Add(&IDispatch),
+        // Add is called through `GuiCollection::of_strings`, as it takes any variant.
         fn ElementAt(i32) -> GuiComponent,
     }
 }
@@ -293,6 +342,7 @@ com_shim! {
         ContainerType: bool,
         Id: String,
         Name: String,
+        Parent: GuiComponent,
         r#Type: String,
         TypeAsNumber: i32,
     }
@@ -310,6 +360,57 @@ com_shim! {
     }
 }
 
+impl GuiCollection {
+    /// Collect every element of this collection as a component.
+    pub fn components(&self) -> crate::Result<Vec<GuiComponent>> {
+        (0..self.count()?).map(|i| self.element_at(i)).collect()
+    }
+
+    /// Read every element of this collection as a string.
+    pub fn strings(&self) -> crate::Result<Vec<String>> {
+        (0..self.count()?)
+            .map(|i| {
+                self.inner
+                    .call("ElementAt", vec![VARIANT::variant_from(i)])?
+                    .variant_into()
+            })
+            .collect()
+    }
+
+    /// Create a collection of strings, as taken by properties such as `GuiGridView::ColumnOrder`.
+    /// Collections can only be created by the application, so any component is needed to find it.
+    pub(crate) fn of_strings<S: AsRef<str>>(
+        component: &GuiComponent,
+        items: &[S],
+    ) -> crate::Result<GuiCollection> {
+        let collection = component.application()?.create_gui_collection()?;
+        for item in items {
+            collection.inner.call(
+                "Add",
+                vec![VARIANT::variant_from(item.as_ref().to_string())],
+            )?;
+        }
+        Ok(collection)
+    }
+}
+
+/// Read the strings of a collection returned by a call. Controls return nothing rather than an
+/// empty collection when there is nothing to return, so that is read as an empty list.
+pub(crate) fn collection_strings(result: &VARIANT) -> crate::Result<Vec<String>> {
+    let collection: Option<&IDispatch> = result.variant_into().ok();
+    match collection {
+        Some(collection) => GuiCollection::from(collection.clone()).strings(),
+        None => Ok(vec![]),
+    }
+}
+
+impl GuiComponentCollection {
+    /// Collect every component in this collection.
+    pub fn to_vec(&self) -> crate::Result<Vec<GuiComponent>> {
+        (0..self.count()?).map(|i| self.element_at(i)).collect()
+    }
+}
+
 com_shim! {
     struct GuiConnection: GuiContainer + GuiComponent {
         Children: GuiComponentCollection,
@@ -428,15 +529,15 @@ sap_type!(GuiGraphAdapt, "GuiShell", "GraphAdapt");
 com_shim! {
     struct GuiGridView: GuiVComponent + GuiVContainer + GuiComponent + GuiContainer + GuiShell {
         ColumnCount: i32,
-        // TODO mut ColumnOrder: Object,
+        mut ColumnOrder: GuiCollection,
         mut CurrentCellColumn: String,
         mut CurrentCellRow: i32,
         mut FirstVisibleColumn: String,
         mut FirstVisibleRow: i32,
         FrozenColumnCount: i32,
         RowCount: i32,
-        // TODO mut SelectedCells: Object,
-        // TODO mut SelectedColumns: Object,
+        mut SelectedCells: GuiCollection,
+        mut SelectedColumns: GuiCollection,
         mut SelectedRows: String,
         SelectionMode: String,
         Title: String,
@@ -474,7 +575,7 @@ com_shim! {
         fn GetColumnOperationType(String) -> String,
         fn GetColumnPosition(String) -> i32,
         fn GetColumnSortType(String) -> String,
-        // TODO fn GetColumnTitles(String) -> Object,
+        fn GetColumnTitles(String) -> GuiCollection,
         fn GetColumnTooltip(String) -> String,
         fn GetColumnTotalType(String) -> String,
         fn GetDisplayedColumnTitle(String) -> String,
@@ -1079,14 +1180,14 @@ com_shim! {
         fn ExpandNode(String),
         fn FindNodeKeyByPath(String) -> String,
         fn GetAbapImage(String, String) -> String,
-        // TODO fn GetAllNodeKeys() -> Object,
+        fn GetAllNodeKeys() -> GuiCollection,
         fn GetCheckBoxState(String, String) -> bool,
-        // TODO fn GetColumnCol(String) -> Object,
-        // TODO fn GetColumnHeaders() -> Object,
+        fn GetColumnCol(String) -> GuiCollection,
+        fn GetColumnHeaders() -> GuiCollection,
         fn GetColumnIndexFromName(String) -> i32,
-        // TODO fn GetColumnNames() -> Object,
+        fn GetColumnNames() -> GuiCollection,
         fn GetColumnTitleFromName(String) -> String,
-        // TODO fn GetColumnTitles() -> Object,
+        fn GetColumnTitles() -> GuiCollection,
         fn GetFocusedNodeKey() -> String,
         fn GetHierarchyLevel(String) -> i32,
         fn GetHierarchyTitle() -> String,
@@ -1109,11 +1210,11 @@ com_shim! {
         fn GetNodeChildrenCountByPath(String) -> i32,
         fn GetNodeHeight(String) -> i32,
         fn GetNodeIndex(String) -> i32,
-        // TODO fn GetNodeItemHeaders(String) -> Object,
+        fn GetNodeItemHeaders(String) -> GuiCollection,
         fn GetNodeKeyByPath(String) -> String,
         fn GetNodeLeft(String) -> i32,
         fn GetNodePathByKey(String) -> String,
-        // TODO fn GetNodesCol() -> Object,
+        fn GetNodesCol() -> GuiCollection,
         fn GetNodeStyle(String) -> i32,
         fn GetNodeTextByKey(String) -> String,
         fn GetNodeTextByPath(String) -> String,
@@ -1123,10 +1224,10 @@ com_shim! {
         fn GetNodeWidth(String) -> i32,
         fn GetParent(String) -> String,
         fn GetPreviousNodeKey(String) -> String,
-        // TODO fn GetSelectedNodes() -> Object,
+        fn GetSelectedNodes() -> GuiCollection,
         fn GetSelectionMode() -> i16,
         fn GetStyleDescription(i32) -> String,
-        // TODO fn GetSubNodesCol(String) -> Object,
+        fn GetSubNodesCol(String) -> GuiCollection,
         fn GetTreeType() -> i32,
         fn HeaderContextMenu(String),
         fn IsFolder(String) -> bool,
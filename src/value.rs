@@ -0,0 +1,449 @@
+use std::fmt;
+
+use com_shim::HasIDispatch;
+
+use crate::Error;
+use crate::types::*;
+
+/// A value that can be read from or written to an input component.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum SapValue {
+    /// Text, for text fields and grid cells. A combo box selects the entry showing the text.
+    Text(String),
+    /// The key of a combo box entry.
+    Key(String),
+    /// Whether a check box is checked or a radio button is selected.
+    Bool(bool),
+    /// Select a radio button.
+    Selected,
+}
+
+impl SapValue {
+    /// The value as a flag, as for check boxes. Text is true when it is `X` or `true`, and false
+    /// when it is blank or `false`.
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            SapValue::Bool(value) => Some(*value),
+            SapValue::Selected => Some(true),
+            SapValue::Text(text) => match text.trim().to_ascii_lowercase().as_str() {
+                "x" | "true" => Some(true),
+                "" | "false" => Some(false),
+                _ => None,
+            },
+            SapValue::Key(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for SapValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SapValue::Text(text) => write!(f, "text \"{text}\""),
+            SapValue::Key(key) => write!(f, "key \"{key}\""),
+            SapValue::Bool(value) => write!(f, "{value}"),
+            SapValue::Selected => write!(f, "selected"),
+        }
+    }
+}
+
+impl From<&str> for SapValue {
+    fn from(value: &str) -> Self {
+        SapValue::Text(value.to_string())
+    }
+}
+
+impl From<String> for SapValue {
+    fn from(value: String) -> Self {
+        SapValue::Text(value)
+    }
+}
+
+impl From<bool> for SapValue {
+    fn from(value: bool) -> Self {
+        SapValue::Bool(value)
+    }
+}
+
+impl From<&SapValue> for SapValue {
+    fn from(value: &SapValue) -> Self {
+        value.clone()
+    }
+}
+
+/// Why a value could not be read from or written to a field.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FieldProblem {
+    /// The component does not hold a value, e.g. a button.
+    Unsupported {
+        /// The type of the component.
+        kind: String,
+    },
+    /// The value is of a kind the component cannot take, e.g. text for a radio button.
+    WrongValue(SapValue),
+    /// The field cannot be changed.
+    ReadOnly,
+    /// The field only shows a value and never takes input.
+    OutputOnly,
+    /// The text is longer than the field, so it would be cut off.
+    TooLong {
+        /// The most characters the field takes.
+        max_length: usize,
+        /// The number of characters in the text.
+        length: usize,
+    },
+    /// The field only takes digits, but the text has others.
+    NotNumeric(String),
+}
+
+impl fmt::Display for FieldProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FieldProblem::Unsupported { kind } => write!(f, "a {kind} has no value"),
+            FieldProblem::WrongValue(value) => write!(f, "cannot be set to {value}"),
+            FieldProblem::ReadOnly => write!(f, "cannot be changed"),
+            FieldProblem::OutputOnly => write!(f, "is output only"),
+            FieldProblem::TooLong { max_length, length } => write!(
+                f,
+                "takes at most {max_length} characters, but the text has {length}"
+            ),
+            FieldProblem::NotNumeric(text) => write!(f, "only takes digits, not \"{text}\""),
+        }
+    }
+}
+
+/// Reading and writing the value of an input component, whatever its type.
+pub trait FieldValue {
+    /// Read the value: text for text fields, the key for combo boxes and a flag for check boxes
+    /// and radio buttons.
+    fn get_value(&self) -> crate::Result<SapValue, Error>;
+
+    /// Write a value, using whichever call the component needs.
+    fn set_value(&self, value: SapValue) -> crate::Result<(), Error>;
+}
+
+/// The error for a value that a field cannot take.
+fn wrong_value<C: GuiComponentExt>(component: &C, value: SapValue) -> Error {
+    Error::Field {
+        id: component.id().unwrap_or_default(),
+        problem: FieldProblem::WrongValue(value),
+    }
+}
+
+macro_rules! text_field_value {
+    ($($ty: ty),*) => {
+        $(
+            impl FieldValue for $ty {
+                fn get_value(&self) -> crate::Result<SapValue, Error> {
+                    Ok(SapValue::Text(self.text()?))
+                }
+
+                fn set_value(&self, value: SapValue) -> crate::Result<(), Error> {
+                    match value {
+                        SapValue::Text(text) => Ok(self.set_text(text)?),
+                        value => Err(wrong_value(self, value)),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+text_field_value!(
+    GuiTextField,
+    GuiCTextField,
+    GuiPasswordField,
+    GuiOkCodeField
+);
+
+impl FieldValue for GuiTextedit {
+    fn get_value(&self) -> crate::Result<SapValue, Error> {
+        Ok(SapValue::Text(self.full_text()?))
+    }
+
+    fn set_value(&self, value: SapValue) -> crate::Result<(), Error> {
+        match value {
+            SapValue::Text(text) => Ok(self.set_full_text(&text)?),
+            value => Err(wrong_value(self, value)),
+        }
+    }
+}
+
+impl FieldValue for GuiComboBox {
+    fn get_value(&self) -> crate::Result<SapValue, Error> {
+        Ok(SapValue::Key(self.key()?))
+    }
+
+    fn set_value(&self, value: SapValue) -> crate::Result<(), Error> {
+        match value {
+            SapValue::Key(key) if key.trim().is_empty() => Ok(self.set_key_space()?),
+            SapValue::Key(key) => Ok(self.set_key(key)?),
+            SapValue::Text(text) => self.select_by_text(text).map(|_| ()),
+            value => Err(wrong_value(self, value)),
+        }
+    }
+}
+
+impl FieldValue for GuiComboBoxControl {
+    fn get_value(&self) -> crate::Result<SapValue, Error> {
+        Ok(SapValue::Key(self.selected()?))
+    }
+
+    fn set_value(&self, value: SapValue) -> crate::Result<(), Error> {
+        match value {
+            SapValue::Key(key) => {
+                self.set_selected(key)?;
+                Ok(self.fire_selected()?)
+            }
+            SapValue::Text(text) => self.select_by_text(text).map(|_| ()),
+            value => Err(wrong_value(self, value)),
+        }
+    }
+}
+
+impl FieldValue for GuiCheckBox {
+    fn get_value(&self) -> crate::Result<SapValue, Error> {
+        Ok(SapValue::Bool(self.selected()?))
+    }
+
+    fn set_value(&self, value: SapValue) -> crate::Result<(), Error> {
+        match value.as_bool() {
+            Some(selected) => Ok(self.set_selected(selected)?),
+            None => Err(wrong_value(self, value)),
+        }
+    }
+}
+
+impl FieldValue for GuiRadioButton {
+    fn get_value(&self) -> crate::Result<SapValue, Error> {
+        Ok(SapValue::Bool(self.selected()?))
+    }
+
+    /// Select the radio button. A radio button cannot be deselected, only another one in its
+    /// group selected, so a false value is accepted only if it is already not selected.
+    fn set_value(&self, value: SapValue) -> crate::Result<(), Error> {
+        match value.as_bool() {
+            Some(true) => Ok(self.select()?),
+            Some(false) if !self.selected()? => Ok(()),
+            _ => Err(wrong_value(self, value)),
+        }
+    }
+}
+
+/// A cell of a [`GuiGridView`], to read and write its value.
+pub struct GridCell<'a> {
+    /// The grid the cell is in.
+    pub grid: &'a GuiGridView,
+    /// The row of the cell, counting from zero.
+    pub row: i32,
+    /// The technical name of the column of the cell.
+    pub column: String,
+}
+
+impl GuiGridView {
+    /// A cell of this grid, by row and technical column name.
+    pub fn cell<S: Into<String>>(&self, row: i32, column: S) -> GridCell<'_> {
+        GridCell {
+            grid: self,
+            row,
+            column: column.into(),
+        }
+    }
+}
+
+impl GridCell<'_> {
+    fn is_checkbox(&self) -> crate::Result<bool> {
+        Ok(self
+            .grid
+            .get_cell_type(self.row, self.column.clone())?
+            .eq_ignore_ascii_case("checkbox"))
+    }
+
+    fn id(&self) -> String {
+        let grid = self.grid.id().unwrap_or_default();
+        format!("{grid}[{},{}]", self.row, self.column)
+    }
+}
+
+impl FieldValue for GridCell<'_> {
+    fn get_value(&self) -> crate::Result<SapValue, Error> {
+        let (row, column) = (self.row, self.column.clone());
+        Ok(if self.is_checkbox()? {
+            SapValue::Bool(self.grid.get_cell_check_box_checked(row, column)?)
+        } else {
+            SapValue::Text(self.grid.get_cell_value(row, column)?)
+        })
+    }
+
+    fn set_value(&self, value: SapValue) -> crate::Result<(), Error> {
+        let (row, column) = (self.row, self.column.clone());
+        if self.is_checkbox()? {
+            if let Some(checked) = value.as_bool() {
+                return Ok(self.grid.modify_check_box(row, column, checked)?);
+            }
+        } else if let SapValue::Text(text) = value {
+            return Ok(self.grid.modify_cell(row, column, text)?);
+        }
+        Err(Error::Field {
+            id: self.id(),
+            problem: FieldProblem::WrongValue(value),
+        })
+    }
+}
+
+impl FieldValue for GuiComponent {
+    /// Read the value of whatever input component this is.
+    fn get_value(&self) -> crate::Result<SapValue, Error> {
+        self.as_field()?.get_value()
+    }
+
+    /// Write the value of whatever input component this is.
+    fn set_value(&self, value: SapValue) -> crate::Result<(), Error> {
+        self.as_field()?.set_value(value)
+    }
+}
+
+impl GuiComponent {
+    /// This component as its own type, to read and write its value.
+    pub fn as_field(&self) -> crate::Result<Box<dyn FieldValue>, Error> {
+        let kind = self.kind()?;
+        let dispatch = self.get_idispatch().clone();
+        Ok(match kind.as_str() {
+            "GuiTextField" => Box::new(GuiTextField::from(dispatch)),
+            "GuiCTextField" => Box::new(GuiCTextField::from(dispatch)),
+            "GuiPasswordField" => Box::new(GuiPasswordField::from(dispatch)),
+            "GuiOkCodeField" => Box::new(GuiOkCodeField::from(dispatch)),
+            "GuiComboBox" => Box::new(GuiComboBox::from(dispatch)),
+            "ComboBoxControl" => Box::new(GuiComboBoxControl::from(dispatch)),
+            "GuiCheckBox" => Box::new(GuiCheckBox::from(dispatch)),
+            "GuiRadioButton" => Box::new(GuiRadioButton::from(dispatch)),
+            "Textedit" => Box::new(GuiTextedit::from(dispatch)),
+            _ => {
+                return Err(Error::Field {
+                    id: self.id()?,
+                    problem: FieldProblem::Unsupported { kind },
+                });
+            }
+        })
+    }
+}
+
+impl GuiComponent {
+    /// Check that a value can be written to this component as it is: the component must be
+    /// changeable and not output only, and text for a text field must fit it and, for numeric
+    /// fields, be only digits.
+    pub fn check_value(&self, value: &SapValue) -> crate::Result<(), Error> {
+        match check(self, value)? {
+            Some(problem) => Err(Error::Field {
+                id: self.id()?,
+                problem,
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Check a value with [`GuiComponent::check_value`], then write it.
+    pub fn set_value_checked(&self, value: SapValue) -> crate::Result<(), Error> {
+        self.check_value(&value)?;
+        self.set_value(value)
+    }
+}
+
+fn check(component: &GuiComponent, value: &SapValue) -> crate::Result<Option<FieldProblem>> {
+    let dispatch = component.get_idispatch().clone();
+    let is_text_field = matches!(
+        component.r_type()?.as_str(),
+        "GuiTextField" | "GuiCTextField" | "GuiPasswordField"
+    );
+    if is_text_field {
+        let field = GuiTextField::from(dispatch);
+        return match value {
+            SapValue::Text(text) => check_text(&field, text),
+            _ => check_input(&field),
+        };
+    }
+    if !GuiVComponent::from(dispatch).changeable()? {
+        return Ok(Some(FieldProblem::ReadOnly));
+    }
+    Ok(None)
+}
+
+/// Check that a text field takes input at all.
+fn check_input<F: GuiTextFieldExt + GuiVComponentExt>(
+    field: &F,
+) -> crate::Result<Option<FieldProblem>> {
+    if field.is_o_field()? {
+        return Ok(Some(FieldProblem::OutputOnly));
+    }
+    if !field.changeable()? {
+        return Ok(Some(FieldProblem::ReadOnly));
+    }
+    Ok(None)
+}
+
+/// Check that a text field takes input, and that text fits it and, for numeric fields, is only
+/// digits.
+fn check_text<F: GuiTextFieldExt + GuiVComponentExt>(
+    field: &F,
+    text: &str,
+) -> crate::Result<Option<FieldProblem>> {
+    if let Some(problem) = check_input(field)? {
+        return Ok(Some(problem));
+    }
+    let length = text.chars().count();
+    let max_length = field.max_length()?;
+    if max_length > 0 && length > max_length as usize {
+        return Ok(Some(FieldProblem::TooLong {
+            max_length: max_length as usize,
+            length,
+        }));
+    }
+    if field.numerical()? && !text.trim().chars().all(|c| c.is_ascii_digit()) {
+        return Ok(Some(FieldProblem::NotNumeric(text.to_string())));
+    }
+    Ok(None)
+}
+
+macro_rules! checked_text_setter {
+    ($($ty: ty),*) => {
+        $(
+            impl $ty {
+                /// Set the text after checking that the field takes input, that the text fits
+                /// without being cut off and, for numeric fields, that it is only digits.
+                pub fn set_text_checked<S: Into<String>>(&self, text: S) -> crate::Result<(), Error> {
+                    let text = text.into();
+                    if let Some(problem) = check_text(self, &text)? {
+                        return Err(Error::Field {
+                            id: self.id()?,
+                            problem,
+                        });
+                    }
+                    Ok(self.set_text(text)?)
+                }
+            }
+        )*
+    };
+}
+
+checked_text_setter!(GuiTextField, GuiCTextField, GuiPasswordField);
+
+impl GuiSession {
+    /// Find a component by ID and write its value, whatever type of input component it is.
+    pub fn set_value<V: Into<SapValue>>(&self, id: &str, value: V) -> crate::Result<(), Error> {
+        self.find_by_id(id.to_string())?.set_value(value.into())
+    }
+
+    /// Find a component by ID, check the value with [`GuiComponent::check_value`] and write it.
+    pub fn set_value_checked<V: Into<SapValue>>(
+        &self,
+        id: &str,
+        value: V,
+    ) -> crate::Result<(), Error> {
+        self.find_by_id(id.to_string())?
+            .set_value_checked(value.into())
+    }
+
+    /// Find a component by ID and read its value, whatever type of input component it is.
+    pub fn get_value(&self, id: &str) -> crate::Result<SapValue, Error> {
+        self.find_by_id(id.to_string())?.get_value()
+    }
+}
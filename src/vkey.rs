@@ -0,0 +1,199 @@
+use std::fmt;
+
+use crate::types::*;
+
+macro_rules! vkeys {
+    ($($(#[$meta: meta])* $variant: ident = $code: expr, $name: expr;)*) => {
+        /// A virtual key, as accepted by `GuiFrameWindow::SendVKey`.
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        pub enum VKey {
+            $($(#[$meta])* $variant,)*
+        }
+
+        impl VKey {
+            /// Every virtual key known to SAP GUI, in code order.
+            pub const ALL: &'static [VKey] = &[$(VKey::$variant,)*];
+
+            /// The key combination that triggers this virtual key on a standard keyboard, e.g. `Ctrl+F`.
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $(VKey::$variant => $name,)*
+                }
+            }
+        }
+
+        impl From<VKey> for i16 {
+            fn from(value: VKey) -> Self {
+                match value {
+                    $(VKey::$variant => $code,)*
+                }
+            }
+        }
+
+        impl TryFrom<i16> for VKey {
+            type Error = UnknownVKey;
+
+            fn try_from(value: i16) -> Result<Self, Self::Error> {
+                match value {
+                    $($code => Ok(VKey::$variant),)*
+                    _ => Err(UnknownVKey(value)),
+                }
+            }
+        }
+    };
+}
+
+vkeys! {
+    Enter = 0, "Enter";
+    F1 = 1, "F1";
+    F2 = 2, "F2";
+    /// Back
+    F3 = 3, "F3";
+    F4 = 4, "F4";
+    F5 = 5, "F5";
+    F6 = 6, "F6";
+    F7 = 7, "F7";
+    /// Execute
+    F8 = 8, "F8";
+    F9 = 9, "F9";
+    F10 = 10, "F10";
+    /// Save
+    F11 = 11, "F11";
+    /// Cancel
+    F12 = 12, "F12";
+    ShiftF1 = 13, "Shift+F1";
+    ShiftF2 = 14, "Shift+F2";
+    ShiftF3 = 15, "Shift+F3";
+    ShiftF4 = 16, "Shift+F4";
+    ShiftF5 = 17, "Shift+F5";
+    ShiftF6 = 18, "Shift+F6";
+    ShiftF7 = 19, "Shift+F7";
+    ShiftF8 = 20, "Shift+F8";
+    ShiftF9 = 21, "Shift+F9";
+    ShiftCtrl0 = 22, "Shift+Ctrl+0";
+    ShiftF11 = 23, "Shift+F11";
+    ShiftF12 = 24, "Shift+F12";
+    CtrlF1 = 25, "Ctrl+F1";
+    CtrlF2 = 26, "Ctrl+F2";
+    CtrlF3 = 27, "Ctrl+F3";
+    CtrlF4 = 28, "Ctrl+F4";
+    CtrlF5 = 29, "Ctrl+F5";
+    CtrlF6 = 30, "Ctrl+F6";
+    CtrlF7 = 31, "Ctrl+F7";
+    CtrlF8 = 32, "Ctrl+F8";
+    CtrlF9 = 33, "Ctrl+F9";
+    CtrlF10 = 34, "Ctrl+F10";
+    CtrlF11 = 35, "Ctrl+F11";
+    CtrlF12 = 36, "Ctrl+F12";
+    CtrlShiftF1 = 37, "Ctrl+Shift+F1";
+    CtrlShiftF2 = 38, "Ctrl+Shift+F2";
+    CtrlShiftF3 = 39, "Ctrl+Shift+F3";
+    CtrlShiftF4 = 40, "Ctrl+Shift+F4";
+    CtrlShiftF5 = 41, "Ctrl+Shift+F5";
+    CtrlShiftF6 = 42, "Ctrl+Shift+F6";
+    CtrlShiftF7 = 43, "Ctrl+Shift+F7";
+    CtrlShiftF8 = 44, "Ctrl+Shift+F8";
+    CtrlShiftF9 = 45, "Ctrl+Shift+F9";
+    CtrlShiftF10 = 46, "Ctrl+Shift+F10";
+    CtrlShiftF11 = 47, "Ctrl+Shift+F11";
+    CtrlShiftF12 = 48, "Ctrl+Shift+F12";
+    CtrlE = 70, "Ctrl+E";
+    /// Find
+    CtrlF = 71, "Ctrl+F";
+    CtrlSlash = 72, "Ctrl+/";
+    CtrlBackslash = 73, "Ctrl+\\";
+    CtrlN = 74, "Ctrl+N";
+    CtrlO = 75, "Ctrl+O";
+    CtrlX = 76, "Ctrl+X";
+    CtrlC = 77, "Ctrl+C";
+    CtrlV = 78, "Ctrl+V";
+    CtrlZ = 79, "Ctrl+Z";
+    CtrlPageUp = 80, "Ctrl+PageUp";
+    PageUp = 81, "PageUp";
+    PageDown = 82, "PageDown";
+    CtrlPageDown = 83, "Ctrl+PageDown";
+    CtrlG = 84, "Ctrl+G";
+    CtrlR = 85, "Ctrl+R";
+    CtrlP = 86, "Ctrl+P";
+}
+
+impl VKey {
+    /// Navigate back (F3).
+    pub const BACK: VKey = VKey::F3;
+    /// Execute (F8).
+    pub const EXECUTE: VKey = VKey::F8;
+    /// Save (F11).
+    pub const SAVE: VKey = VKey::F11;
+    /// Cancel (F12).
+    pub const CANCEL: VKey = VKey::F12;
+    /// Find (Ctrl+F).
+    pub const FIND: VKey = VKey::CtrlF;
+}
+
+impl fmt::Display for VKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// The error returned when converting a number that is not in the SAP virtual key table.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UnknownVKey(pub i16);
+
+impl fmt::Display for UnknownVKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} is not a known virtual key", self.0)
+    }
+}
+
+impl std::error::Error for UnknownVKey {}
+
+/// Typed virtual key functions for frame windows.
+pub trait GuiFrameWindowVKeyExt: GuiFrameWindowExt + Sized {
+    /// Send a virtual key to this window.
+    fn send_vkey(&self, key: VKey) -> crate::Result<()> {
+        self.send_v_key(key.into())
+    }
+
+    /// Check if a virtual key is currently allowed in this window.
+    fn is_vkey_allowed(&self, key: VKey) -> crate::Result<bool> {
+        self.is_v_key_allowed(key.into())
+    }
+}
+
+impl<T: GuiFrameWindowExt> GuiFrameWindowVKeyExt for T {}
+
+/// A virtual key with its standard key name and the description SAP GUI gives it in the logon language.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VKeyLabel {
+    /// The virtual key.
+    pub key: VKey,
+    /// The standard key combination, from [`VKey::name`].
+    pub name: &'static str,
+    /// The description reported by `GuiSession::GetVKeyDescription`.
+    pub description: String,
+}
+
+impl VKeyLabel {
+    /// Whether SAP GUI describes this key the same way as its standard name.
+    /// This is usually false when logged on in a language other than English.
+    pub fn matches_name(&self) -> bool {
+        self.description.trim().eq_ignore_ascii_case(self.name)
+    }
+}
+
+impl GuiSession {
+    /// List every virtual key alongside its language-specific description from this session.
+    pub fn v_key_labels(&self) -> crate::Result<Vec<VKeyLabel>> {
+        VKey::ALL
+            .iter()
+            .map(|&key| {
+                Ok(VKeyLabel {
+                    key,
+                    name: key.name(),
+                    description: self.get_v_key_description(i16::from(key).into())?,
+                })
+            })
+            .collect()
+    }
+}